@@ -0,0 +1,213 @@
+//! Cameras producing view and projection matrices for 3D scenes.
+use crate::frustum::Frustum;
+use crate::math::{Rect, Scalar};
+use na::{Matrix4, Point2, Point3, Unit, UnitQuaternion, Vector3};
+use nalgebra as na;
+use nalgebra_glm as glm;
+
+/// Projection used by a `Camera`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection<F: Scalar> {
+    /// Perspective projection with vertical field of view `fovy`, in
+    /// radians.
+    Perspective { fovy: F, aspect: F, near: F, far: F },
+
+    /// Orthographic projection showing a `height` tall region around
+    /// the view axis.
+    Orthographic {
+        height: F,
+        aspect: F,
+        near: F,
+        far: F,
+    },
+}
+
+/// A camera looking from `eye` toward `target`.
+///
+/// Matrices follow the right-handed, OpenGL-style conventions of
+/// `nalgebra_glm`, so the camera looks down its -z axis and clip
+/// space z ranges over [-1, 1].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera<F: Scalar> {
+    pub eye: Point3<F>,
+    pub target: Point3<F>,
+    pub up: Vector3<F>,
+    pub projection: Projection<F>,
+}
+
+/// Map normalized device coordinates, with x and y in [-1, 1] and y
+/// up, into a page rect with y down.
+pub fn ndc_to_rect<F: Scalar>(ndc: &Point2<F>, rect: &Rect<F>) -> Point2<F> {
+    let half: F = na::convert(0.5);
+    let d = rect.dim();
+    Point2::new(
+        rect.p[0].x + (ndc.x + F::one()) * half * d.x,
+        rect.p[0].y + (F::one() - ndc.y) * half * d.y,
+    )
+}
+
+impl<F: Scalar> Camera<F> {
+    pub fn new(
+        eye: Point3<F>,
+        target: Point3<F>,
+        up: Vector3<F>,
+        projection: Projection<F>,
+    ) -> Camera<F> {
+        Camera {
+            eye,
+            target,
+            up,
+            projection,
+        }
+    }
+
+    /// Create a perspective camera. `fovy` is the vertical field of
+    /// view, in radians.
+    pub fn perspective(
+        eye: Point3<F>,
+        target: Point3<F>,
+        up: Vector3<F>,
+        fovy: F,
+        aspect: F,
+        near: F,
+        far: F,
+    ) -> Camera<F> {
+        let projection = Projection::Perspective {
+            fovy,
+            aspect,
+            near,
+            far,
+        };
+        Self::new(eye, target, up, projection)
+    }
+
+    /// Create an orthographic camera, showing a region `height` tall.
+    pub fn orthographic(
+        eye: Point3<F>,
+        target: Point3<F>,
+        up: Vector3<F>,
+        height: F,
+        aspect: F,
+        near: F,
+        far: F,
+    ) -> Camera<F> {
+        let projection = Projection::Orthographic {
+            height,
+            aspect,
+            near,
+            far,
+        };
+        Self::new(eye, target, up, projection)
+    }
+
+    /// Set the aspect ratio to match the page rect.
+    pub fn fit_aspect(&mut self, rect: &Rect<F>) {
+        let d = rect.dim();
+        match &mut self.projection {
+            Projection::Perspective { aspect, .. } | Projection::Orthographic { aspect, .. } => {
+                *aspect = d.x / d.y
+            }
+        }
+    }
+
+    /// Return the world-to-view matrix.
+    pub fn view_matrix(&self) -> Matrix4<F> {
+        glm::look_at_rh(&self.eye.coords, &self.target.coords, &self.up)
+    }
+
+    /// Return the view-to-clip matrix.
+    pub fn projection_matrix(&self) -> Matrix4<F> {
+        match self.projection {
+            Projection::Perspective {
+                fovy,
+                aspect,
+                near,
+                far,
+            } => glm::perspective_rh(aspect, fovy, near, far),
+            Projection::Orthographic {
+                height,
+                aspect,
+                near,
+                far,
+            } => {
+                let h = height * na::convert(0.5);
+                let w = h * aspect;
+                glm::ortho_rh(-w, w, -h, h, near, far)
+            }
+        }
+    }
+
+    /// Return the world-to-clip matrix.
+    pub fn clip_matrix(&self) -> Matrix4<F> {
+        self.projection_matrix() * self.view_matrix()
+    }
+
+    /// Return the view frustum, in world coordinates.
+    pub fn frustum(&self) -> Frustum<F> {
+        Frustum::from_clip_matrix(&self.clip_matrix())
+    }
+
+    /// Return the camera's forward, right and up directions.
+    pub fn basis(&self) -> (Vector3<F>, Vector3<F>, Vector3<F>) {
+        let forward = (self.target - self.eye).normalize();
+        let right = forward.cross(&self.up).normalize();
+        (forward, right, right.cross(&forward))
+    }
+
+    /// Project a world point to normalized device coordinates.
+    ///
+    /// Returns `None` for points at or behind the eye plane of a
+    /// perspective camera. Points outside of the frustum are not
+    /// rejected.
+    pub fn project_ndc(&self, p: &Point3<F>) -> Option<Point3<F>> {
+        let c = self.clip_matrix() * p.to_homogeneous();
+        if c.w <= F::zero() {
+            return None;
+        }
+        Some(Point3::new(c.x / c.w, c.y / c.w, c.z / c.w))
+    }
+
+    /// Project a world point onto a page rect, with the top of the
+    /// view at the top (minimum y) of the rect.
+    pub fn project_to_rect(&self, p: &Point3<F>, rect: &Rect<F>) -> Option<Point2<F>> {
+        self.project_ndc(p).map(|ndc| ndc_to_rect(&ndc.xy(), rect))
+    }
+
+    /// Orbit the eye around the target, by `yaw` radians about the up
+    /// vector and `pitch` radians toward the up vector.
+    ///
+    /// Pitch is clamped so that the eye never passes over the poles.
+    pub fn orbit(&mut self, yaw: F, pitch: F) {
+        let up = Unit::new_normalize(self.up);
+        let mut offset = UnitQuaternion::from_axis_angle(&up, yaw) * (self.eye - self.target);
+
+        let axis = up.cross(&offset);
+        if axis.norm() > F::zero() {
+            let eps: F = na::convert(1e-3);
+            let polar = (offset.dot(&up) / offset.norm())
+                .max(-F::one())
+                .min(F::one())
+                .acos();
+            let new_polar = (polar - pitch).max(eps).min(F::pi() - eps);
+            let rot =
+                UnitQuaternion::from_axis_angle(&Unit::new_normalize(axis), new_polar - polar);
+            offset = rot * offset;
+        }
+        self.eye = self.target + offset;
+    }
+
+    /// Move the eye toward the target, scaling their distance by
+    /// `factor`.
+    pub fn dolly(&mut self, factor: F) {
+        self.eye = self.target + (self.eye - self.target) * factor;
+    }
+
+    /// Move the eye and target together, by `dx` along the camera's
+    /// right direction and `dy` along its up direction.
+    pub fn pan(&mut self, dx: F, dy: F) {
+        let (_, right, up) = self.basis();
+        let v = right * dx + up * dy;
+        self.eye += v;
+        self.target += v;
+    }
+}
@@ -0,0 +1,227 @@
+//! Convex clipping volumes bounded by any number of planes.
+use crate::frustum::{
+    clip_polygon_plane, clip_polyline_by, clip_result, clip_segment_params, plane_distance,
+    ClipResult, Frustum,
+};
+use crate::math::{Aabb, Obb, Scalar};
+use na::{Point3, Vector3, Vector4};
+use nalgebra as na;
+
+/// A convex region formed by the intersection of half-spaces.
+///
+/// Like `Frustum`, each plane is stored as (n, d) with normalized n,
+/// and a point p is inside the plane when n.p + d >= 0.
+#[derive(Clone, Debug)]
+pub struct ConvexVolume<F: Scalar> {
+    pub planes: Vec<Vector4<F>>,
+}
+
+/// Return the plane through a, b and c with normal (b - a) x (c - a),
+/// or `None` if the points are collinear.
+fn face_plane<F: Scalar>(a: &Point3<F>, b: &Point3<F>, c: &Point3<F>) -> Option<Vector4<F>> {
+    let n = (b - a).cross(&(c - a));
+    let len = n.norm();
+    if len == F::zero() {
+        return None;
+    }
+    let n = n / len;
+    Some(Vector4::new(n.x, n.y, n.z, -n.dot(&a.coords)))
+}
+
+impl<F: Scalar> ConvexVolume<F> {
+    /// Create a volume from planes, normalizing each of them.
+    pub fn from_planes(planes: &[Vector4<F>]) -> ConvexVolume<F> {
+        let planes = planes.iter().map(|p| p / p.xyz().norm()).collect();
+        ConvexVolume { planes }
+    }
+
+    pub fn from_frustum(frustum: &Frustum<F>) -> ConvexVolume<F> {
+        ConvexVolume {
+            planes: frustum.planes.to_vec(),
+        }
+    }
+
+    pub fn from_aabb(b: &Aabb<F>) -> ConvexVolume<F> {
+        let mut planes = Vec::with_capacity(6);
+        for i in 0..3 {
+            let n = Vector3::ith(i, F::one());
+            planes.push(Vector4::new(n.x, n.y, n.z, -b.p[0][i]));
+            planes.push(Vector4::new(-n.x, -n.y, -n.z, b.p[1][i]));
+        }
+        ConvexVolume { planes }
+    }
+
+    pub fn from_obb(b: &Obb<F>) -> ConvexVolume<F> {
+        let mut planes = Vec::with_capacity(6);
+        for i in 0..3 {
+            let n = b.axes[i].normalize();
+            let c = n.dot(&b.center.coords);
+            planes.push(Vector4::new(n.x, n.y, n.z, b.half_widths[i] - c));
+            planes.push(Vector4::new(-n.x, -n.y, -n.z, b.half_widths[i] + c));
+        }
+        ConvexVolume { planes }
+    }
+
+    /// Create the convex hull of the points, using an incremental
+    /// hull.
+    ///
+    /// Returns `None` if the points are coplanar, to within a
+    /// tolerance relative to their extent. Points exactly collinear
+    /// with an edge of the hull built so far are skipped.
+    pub fn convex_hull(points: &[Point3<F>]) -> Option<ConvexVolume<F>> {
+        let bounds = Aabb::bounding(points)?;
+        let eps = bounds.dim().norm() * F::default_epsilon().sqrt();
+
+        // initial tetrahedron from extreme points
+        let farthest = |d: &dyn Fn(&Point3<F>) -> F| {
+            (0..points.len())
+                .map(|i| (i, d(&points[i])))
+                .fold((0, F::zero()), |a, b| if b.1 > a.1 { b } else { a })
+        };
+        let i0 = 0;
+        let (i1, d1) = farthest(&|p| (p - points[i0]).norm());
+        if d1 <= eps {
+            return None;
+        }
+        let axis = (points[i1] - points[i0]) / d1;
+        let (i2, d2) = farthest(&|p| {
+            let v = p - points[i0];
+            (v - axis * axis.dot(&v)).norm()
+        });
+        if d2 <= eps {
+            return None;
+        }
+        let base = face_plane(&points[i0], &points[i1], &points[i2])?;
+        let (i3, d3) = farthest(&|p| plane_distance(&base, p).abs());
+        if d3 <= eps {
+            return None;
+        }
+        let inside = Point3::from(
+            (points[i0].coords + points[i1].coords + points[i2].coords + points[i3].coords)
+                * na::convert::<f64, F>(0.25),
+        );
+
+        // faces wound with outward normals
+        let mut faces: Vec<([usize; 3], Vector4<F>)> = vec![];
+        for &[a, b, c] in &[[i0, i1, i2], [i0, i1, i3], [i0, i2, i3], [i1, i2, i3]] {
+            let plane = face_plane(&points[a], &points[b], &points[c])?;
+            if plane_distance(&plane, &inside) > F::zero() {
+                faces.push(([a, c, b], -plane));
+            } else {
+                faces.push(([a, b, c], plane));
+            }
+        }
+
+        for (i, p) in points.iter().enumerate() {
+            let (visible, hidden): (Vec<&_>, Vec<&_>) = faces
+                .iter()
+                .partition(|(_, plane)| plane_distance(plane, p) > eps);
+            if visible.is_empty() {
+                continue;
+            }
+
+            // edges of the visible region whose twins are not visible
+            let edges: Vec<(usize, usize)> = visible
+                .iter()
+                .flat_map(|(f, _)| vec![(f[0], f[1]), (f[1], f[2]), (f[2], f[0])])
+                .collect();
+            let new_faces: Option<Vec<_>> = edges
+                .iter()
+                .filter(|&&(a, b)| !edges.contains(&(b, a)))
+                .map(|&(a, b)| {
+                    face_plane(&points[a], &points[b], p).map(|plane| ([a, b, i], plane))
+                })
+                .collect();
+
+            // a point collinear with a horizon edge would leave a hole,
+            // so treat it as not visible
+            if let Some(new_faces) = new_faces {
+                faces = hidden.into_iter().cloned().chain(new_faces).collect();
+            }
+        }
+
+        // merge the planes of coplanar faces
+        let mut planes: Vec<Vector4<F>> = vec![];
+        for (_, plane) in faces {
+            let plane = -plane;
+            let duplicate = planes.iter().any(|q| {
+                q.xyz().dot(&plane.xyz()) >= F::one() - F::default_epsilon().sqrt()
+                    && (q[3] - plane[3]).abs() <= eps
+            });
+            if !duplicate {
+                planes.push(plane);
+            }
+        }
+        Some(ConvexVolume { planes })
+    }
+
+    /// Return the volume containing points inside both volumes.
+    ///
+    /// The result may be empty.
+    pub fn intersection(&self, other: &ConvexVolume<F>) -> ConvexVolume<F> {
+        let mut planes = self.planes.clone();
+        planes.extend_from_slice(&other.planes);
+        ConvexVolume { planes }
+    }
+
+    /// Return true iff the point lies within the volume.
+    pub fn is_point_in(&self, v: &Point3<F>) -> bool {
+        self.planes
+            .iter()
+            .all(|p| plane_distance(p, v) >= F::zero())
+    }
+
+    /// Return true iff the point lies within or on the volume.
+    pub fn is_point_in_or_on(&self, v: &Point3<F>, eps: F) -> bool {
+        self.planes.iter().all(|p| plane_distance(p, v) >= -eps)
+    }
+
+    /// Return the parameter range (t0, t1) of the segment from `p0`
+    /// to `p1` inside the volume, if it is non-empty.
+    pub fn clip_line_params(&self, p0: &Point3<F>, p1: &Point3<F>) -> Option<(F, F)> {
+        clip_segment_params(
+            self.planes
+                .iter()
+                .map(|pl| (plane_distance(pl, p0), plane_distance(pl, p1))),
+        )
+    }
+
+    /// Clip a 3d line segment against the volume.
+    pub fn clip_line(&self, p0: &Point3<F>, p1: &Point3<F>) -> ClipResult<F> {
+        clip_result(p0, p1, self.clip_line_params(p0, p1))
+    }
+
+    /// Clip a 3d polyline against the volume, returning the runs of
+    /// the polyline inside it.
+    pub fn clip_polyline(&self, points: &[Point3<F>]) -> Vec<Vec<Point3<F>>> {
+        clip_polyline_by(points, |a, b| self.clip_line_params(a, b))
+    }
+
+    /// Clip each of the polylines against the volume.
+    pub fn clip_polylines(&self, polylines: &[Vec<Point3<F>>]) -> Vec<Vec<Point3<F>>> {
+        polylines
+            .iter()
+            .flat_map(|pl| self.clip_polyline(pl))
+            .collect()
+    }
+
+    /// Clip a convex, planar 3d polygon against the volume.
+    ///
+    /// Returns the vertices of the clipped polygon, in the same
+    /// winding order, or an empty list if the polygon is outside.
+    pub fn clip_polygon(&self, poly: &[Point3<F>]) -> Vec<Point3<F>> {
+        let tagged: Vec<(Point3<F>, Option<usize>)> = poly.iter().map(|p| (*p, None)).collect();
+        self.planes
+            .iter()
+            .fold(tagged, |poly, plane| {
+                if poly.is_empty() {
+                    poly
+                } else {
+                    clip_polygon_plane(plane, &poly)
+                }
+            })
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect()
+    }
+}
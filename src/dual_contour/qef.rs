@@ -1,12 +1,18 @@
 use crate::common::*;
-use ndarray::Array2;
-//use ndarray_linalg::QR;
+use na::{Matrix2, Matrix3, Vector3};
+#[cfg(feature = "lapack")]
+use crate::math::types::LapackScalar;
 use super::types::HermiteData;
 
+/// Relative threshold below which singular values are treated as
+/// zero when solving.
+const SVD_TOLERANCE: f64 = 1e-6;
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Debug)]
 pub struct QEF<F: Scalar> {
     // A | b
-    ab_t_ab: Array2<F>,
+    ab_t_ab: Matrix3<F>,
 
     // mass point, to minimize distance to
     mass_point_p: Vector2<F>,
@@ -16,18 +22,123 @@ pub struct QEF<F: Scalar> {
 }
 
 impl<F: Scalar> QEF<F> {
-    pub fn new(points: &Vec<HermiteData<F>>) -> QEF<F> {
-	// Compute the raw a-b matrix.
-	let mut ab: Array2<F> = ndarray::Array2::zeros((points.len(), 3));
+    pub fn new(points: &[HermiteData<F>]) -> QEF<F> {
+	// Accumulate the (A | b)^T (A | b) matrix directly, one row
+	// at a time.
+	let mut ab_t_ab = Matrix3::zeros();
 
 	let mut mp = Vector2::new(F::zero(), F::zero());
-	for (i, p) in points.iter().enumerate() {
-	    ab[[i, 0]] = p.n.x;
-	    ab[[i, 1]] = p.n.y;
-	    ab[[i, 2]] = p.n.dot(&p.p.coords);
+	for p in points.iter() {
+	    let row = Vector3::new(p.n.x, p.n.y, p.n.dot(&p.p.coords));
+	    ab_t_ab += row * row.transpose();
 	    mp += p.p.coords;
 	}
 
-	QEF { ab_t_ab: ab.t().dot(&ab), mass_point_p: mp, mass_point_dim: points.len() }
+	QEF { ab_t_ab, mass_point_p: mp, mass_point_dim: points.len() }
+    }
+
+    /// Return the average of the intersection points.
+    pub fn mass_point(&self) -> Vector2<F> {
+	if self.mass_point_dim == 0 {
+	    return Vector2::zeros();
+	}
+	self.mass_point_p / na::convert::<f64, F>(self.mass_point_dim as f64)
+    }
+
+    /// Return (A^T A, A^T b - A^T A m, m), the normal equations of
+    /// the error function relative to the mass point m.
+    fn normal_equations(&self) -> (Matrix2<F>, Vector2<F>, Vector2<F>) {
+	let m = &self.ab_t_ab;
+	let ata = Matrix2::new(m[(0, 0)], m[(0, 1)], m[(1, 0)], m[(1, 1)]);
+	let atb = Vector2::new(m[(0, 2)], m[(1, 2)]);
+	let mp = self.mass_point();
+	(ata, atb - ata * mp, mp)
+    }
+
+    /// Return the point minimizing the error function.
+    ///
+    /// Directions the error function does not constrain are resolved
+    /// toward the mass point. Uses nalgebra's SVD.
+    pub fn solve(&self) -> Point2<F> {
+	let (ata, rhs, mp) = self.normal_equations();
+	let svd = ata.svd(true, true);
+	let eps = svd.singular_values.max() * na::convert(SVD_TOLERANCE);
+	let x = svd.solve(&rhs, eps).unwrap_or_else(|_| Vector2::zeros());
+	Point2::from(mp + x)
+    }
+}
+
+#[cfg(feature = "lapack")]
+impl<F: LapackScalar> QEF<F> {
+    /// Same as `solve`, but uses LAPACK's SVD.
+    pub fn solve_lapack(&self) -> Point2<F> {
+	use ndarray_linalg::SVD;
+
+	let (ata, rhs, mp) = self.normal_equations();
+	let a = ndarray::arr2(&[[ata[(0, 0)], ata[(0, 1)]], [ata[(1, 0)], ata[(1, 1)]]]);
+
+	// x = V S^+ U^T rhs
+	let mut x = Vector2::zeros();
+	if let Ok((Some(u), s, Some(vt))) = a.svd(true, true) {
+	    let eps = s[0] * na::convert(SVD_TOLERANCE);
+	    for i in 0..2 {
+		if s[i] > eps {
+		    let c = (u[[0, i]] * rhs.x + u[[1, i]] * rhs.y) / s[i];
+		    x += Vector2::new(vt[[i, 0]], vt[[i, 1]]) * c;
+		}
+	    }
+	}
+	Point2::from(mp + x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hermite data for the lines x = 1 and x + y = 3, which cross at
+    /// (1, 2).
+    fn crossing_lines() -> QEF<f64> {
+	let d = 0.5f64.sqrt();
+	QEF::new(&[
+	    HermiteData {
+		p: Point2::new(1.0, 0.5),
+		n: Vector2::new(1.0, 0.0),
+	    },
+	    HermiteData {
+		p: Point2::new(2.5, 0.5),
+		n: Vector2::new(d, d),
+	    },
+	])
+    }
+
+    #[test]
+    fn test_solve_corner() {
+	let p = crossing_lines().solve();
+	assert!((p - Point2::new(1.0, 2.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_solve_parallel() {
+	// the lines don't fix y, so it comes from the mass point
+	let q: QEF<f64> = QEF::new(&[
+	    HermiteData {
+		p: Point2::new(1.0, 0.0),
+		n: Vector2::new(1.0, 0.0),
+	    },
+	    HermiteData {
+		p: Point2::new(1.0, 1.0),
+		n: Vector2::new(-1.0, 0.0),
+	    },
+	]);
+	let p = q.solve();
+	assert!((p - Point2::new(1.0, 0.5)).norm() < 1e-9);
+    }
+
+    #[cfg(feature = "lapack")]
+    #[test]
+    fn test_solve_lapack() {
+	let q = crossing_lines();
+	assert!((q.solve_lapack() - q.solve()).norm() < 1e-9);
     }
 }
@@ -14,6 +14,7 @@ pub enum CellClass {
     Mixed
 }
 
+#[allow(clippy::upper_case_acronyms)]
 pub trait SDF<F: Scalar> {
     fn eval_f(&self, p: Point2<F>) -> F;
 
@@ -32,16 +33,16 @@ impl<T, F: Scalar> SDF<F> for T where T: Fn(Point2<F>) -> F {
     }
 }
 
-/// Quadtree order:
-/// -------2--------
-/// |     +y       |
-/// |  3   |   2   |
-/// |      |       |
-/// 3-x---------+x 1
-/// |      |       |
-/// |  0   |   1   |
-/// |     -y       |
-/// -------0--------
+// Quadtree order:
+// -------2--------
+// |     +y       |
+// |  3   |   2   |
+// |      |       |
+// 3-x---------+x 1
+// |      |       |
+// |  0   |   1   |
+// |     -y       |
+// -------0--------
 
 /// Quadtree Leaf node.
 ///
@@ -271,10 +272,7 @@ impl<F: Scalar> QtNode<F> {
 
     /// Return true iff this represents a leaf.
     fn is_leaf(&self) -> bool {
-	match self {
-	    &QtNode::Leaf(_) => true,
-	    _ => false
-	}
+	matches!(self, QtNode::Leaf(_))
     }
 
     /// Return the number of leaves in the subtree rooted at this
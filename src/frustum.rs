@@ -1,6 +1,8 @@
-use na::{Point3, Vector4};
+use crate::math::{Aabb, Obb, Scalar};
+use na::{Matrix3, Point3, Vector3, Vector4};
 use nalgebra as na;
-use std::cmp::Ordering;
+use std::convert::{TryFrom, TryInto};
+use std::fmt::Debug;
 //use ncollide2d as nc;
 
 #[derive(Clone, Copy)]
@@ -20,6 +22,36 @@ pub struct Frustum<F: na::RealField> {
     pub planes: [na::Vector4<F>; 6],
 }
 
+/// Result of testing a volume against a `Frustum`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+
+    /// The volume may be partially inside the frustum. Volumes near
+    /// the frustum corners may be classified as intersecting when
+    /// they are actually outside.
+    Intersecting,
+
+    Outside,
+}
+
+/// Scheme for placing the boundaries when splitting a frustum into
+/// depth ranges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DepthSplit<F> {
+    /// Evenly spaced boundaries.
+    Uniform,
+
+    /// Boundaries spaced evenly in log-depth, so that each range
+    /// covers the same ratio of far to near distance.
+    Logarithmic,
+
+    /// A blend of the logarithmic and uniform schemes, weighted by
+    /// the given factor in [0, 1] (1 is fully logarithmic).
+    Practical(F),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ClipResultPartial {
     /// The first point is in, the frustum, but the second point is
     /// outside.
@@ -34,6 +66,7 @@ pub enum ClipResultPartial {
 }
 
 /// Result from clipping a line segment against a `Frustum`.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ClipResult<F: na::RealField> {
     /// The line segment is completely outside of the frustum.
     Outside,
@@ -48,21 +81,171 @@ pub enum ClipResult<F: na::RealField> {
     Partial(ClipResultPartial, Point3<F>, Point3<F>),
 }
 
-/// Return the value t along the line p0 + p1 * t that intersects with
-/// the plane, if it exists.
-fn plane_segment_directed_intersection<F: na::RealField>(
-    plane: &Vector4<F>,
+/// Clip the parameter range [0, 1] of a segment against a set of
+/// half-spaces, using Liang-Barsky.
+///
+/// Each item is the pair of signed distances (d0, d1) of the segment
+/// endpoints from a plane, with positive values inside. Returns the
+/// range (t0, t1) of the segment inside all of the half-spaces, if it
+/// is non-empty.
+pub(crate) fn clip_segment_params<F: na::RealField, I: IntoIterator<Item = (F, F)>>(
+    dists: I,
+) -> Option<(F, F)> {
+    let zero = F::zero();
+    let (mut t0, mut t1) = (zero, F::one());
+    for (d0, d1) in dists {
+        if d0 < zero && d1 < zero {
+            return None;
+        }
+        if d0 >= zero && d1 >= zero {
+            continue;
+        }
+        // the endpoints are on opposite sides, so d0 != d1
+        let t = d0 / (d0 - d1);
+        if d0 < zero {
+            // entering
+            t0 = t0.max(t);
+        } else {
+            t1 = t1.min(t);
+        }
+        if t0 >= t1 {
+            return None;
+        }
+    }
+    Some((t0, t1))
+}
+
+/// Clip a line segment given in homogeneous clip coordinates against
+/// the canonical view volume, -w <= x, y, z <= w.
+///
+/// Returns the clipped endpoints, still in clip coordinates, or
+/// `None` if the segment is outside. Clipping before the perspective
+/// division handles segments passing behind the eye.
+pub fn clip_line_homogeneous<F: na::RealField>(
+    c0: &Vector4<F>,
+    c1: &Vector4<F>,
+) -> Option<(Vector4<F>, Vector4<F>)> {
+    let dists =
+        (0..3).flat_map(|i| vec![(c0.w + c0[i], c1.w + c1[i]), (c0.w - c0[i], c1.w - c1[i])]);
+    let (t0, t1) = clip_segment_params(dists)?;
+    let d = c1 - c0;
+    Some((c0 + d * t0, c0 + d * t1))
+}
+
+/// Build the `ClipResult` for the segment from `p0` to `p1`, given
+/// the parameter range of the segment inside the clipping volume.
+pub(crate) fn clip_result<F: na::RealField>(
     p0: &Point3<F>,
     p1: &Point3<F>,
-) -> Option<F> {
-    let v = p1 - p0;
-    let pn = plane.xyz();
-    let v_dot_pn = v.dot(&pn);
-    let eps: F = na::convert(1e-5);
-    if v_dot_pn.abs() / v.norm() < eps {
-        return None;
-    }
-    Some((plane[3] + p0.coords.dot(&pn)) / -v_dot_pn)
+    range: Option<(F, F)>,
+) -> ClipResult<F> {
+    let (t0, t1) = match range {
+        Some(t) => t,
+        None => return ClipResult::Outside,
+    };
+    let (zero, one) = (F::zero(), F::one());
+    let d = p1 - p0;
+    let (a, b) = (p0 + d * t0, p0 + d * t1);
+    match (t0 == zero, t1 == one) {
+        (true, true) => ClipResult::Inside(*p0, *p1),
+        (true, false) => ClipResult::Partial(ClipResultPartial::Prefix, *p0, b),
+        (false, true) => ClipResult::Partial(ClipResultPartial::Suffix, a, *p1),
+        (false, false) => ClipResult::Partial(ClipResultPartial::Infix, a, b),
+    }
+}
+
+/// Split a polyline into the runs inside a clipping volume, given a
+/// function returning the parameter range of a segment inside it.
+pub(crate) fn clip_polyline_by<F, C>(points: &[Point3<F>], clip_params: C) -> Vec<Vec<Point3<F>>>
+where
+    F: na::RealField,
+    C: Fn(&Point3<F>, &Point3<F>) -> Option<(F, F)>,
+{
+    let mut runs = vec![];
+    let mut curr: Vec<Point3<F>> = vec![];
+    for w in points.windows(2) {
+        let (t0, t1) = match clip_params(&w[0], &w[1]) {
+            Some(t) => t,
+            None => {
+                if !curr.is_empty() {
+                    runs.push(std::mem::take(&mut curr));
+                }
+                continue;
+            }
+        };
+        let d = w[1] - w[0];
+        // entering the volume starts a new run
+        if t0 > F::zero() && !curr.is_empty() {
+            runs.push(std::mem::take(&mut curr));
+        }
+        if curr.is_empty() {
+            curr.push(w[0] + d * t0);
+        }
+        curr.push(if t1 < F::one() { w[0] + d * t1 } else { w[1] });
+        // leaving the volume ends it
+        if t1 < F::one() {
+            runs.push(std::mem::take(&mut curr));
+        }
+    }
+    if !curr.is_empty() {
+        runs.push(curr);
+    }
+    runs
+}
+
+/// Return the signed distance of the point from the plane, positive
+/// on the inside.
+pub(crate) fn plane_distance<F: na::RealField>(plane: &Vector4<F>, p: &Point3<F>) -> F {
+    plane.xyz().dot(&p.coords) + plane[3]
+}
+
+/// Return the plane through the points a, b and c, oriented so that
+/// `inside` is on the positive side.
+fn plane_from_points<F: na::RealField>(
+    a: &Point3<F>,
+    b: &Point3<F>,
+    c: &Point3<F>,
+    inside: &Point3<F>,
+) -> Vector4<F> {
+    let n = (b - a).cross(&(c - a)).normalize();
+    let plane = Vector4::new(n.x, n.y, n.z, -n.dot(&a.coords));
+    if plane_distance(&plane, inside) < F::zero() {
+        -plane
+    } else {
+        plane
+    }
+}
+
+/// Clip a polygon against a single plane, keeping the inside part.
+///
+/// Each vertex carries the index of the original vertex it came from,
+/// if it was not created by clipping.
+pub(crate) fn clip_polygon_plane<F: na::RealField>(
+    plane: &Vector4<F>,
+    poly: &[(Point3<F>, Option<usize>)],
+) -> Vec<(Point3<F>, Option<usize>)> {
+    let mut out = Vec::with_capacity(poly.len() + 1);
+    let n = poly.len();
+    for i in 0..n {
+        let (prev, curr) = (&poly[(i + n - 1) % n], &poly[i]);
+        let (d_prev, d_curr) = (
+            plane_distance(plane, &prev.0),
+            plane_distance(plane, &curr.0),
+        );
+        let crossing = |a: &Point3<F>, b: &Point3<F>| {
+            let t = d_prev / (d_prev - d_curr);
+            (a + (b - a) * t, None)
+        };
+        if d_curr >= F::zero() {
+            if d_prev < F::zero() {
+                out.push(crossing(&prev.0, &curr.0));
+            }
+            out.push(*curr);
+        } else if d_prev >= F::zero() {
+            out.push(crossing(&prev.0, &curr.0));
+        }
+    }
+    out
 }
 
 impl<F: na::RealField> Frustum<F> {
@@ -102,76 +285,170 @@ impl<F: na::RealField> Frustum<F> {
         self.planes.iter().all(|p| p.dot(&ext) >= -eps)
     }
 
+    /// Return the parameter range (t0, t1) of the segment from `p0`
+    /// to `p1` inside the frustum, if it is non-empty.
+    pub fn clip_line_params(&self, p0: &Point3<F>, p1: &Point3<F>) -> Option<(F, F)> {
+        clip_segment_params(
+            self.planes
+                .iter()
+                .map(|pl| (plane_distance(pl, p0), plane_distance(pl, p1))),
+        )
+    }
+
     /// Clip a 3d line segment against the frustum.
+    ///
+    /// Points on the boundary count as inside. Segments that only
+    /// touch the frustum at a single point are outside.
     pub fn clip_line(&self, p0: &na::Point3<F>, p1: &na::Point3<F>) -> ClipResult<F> {
-        let in0 = self.is_point_in(p0);
-        let in1 = self.is_point_in(p1);
+        clip_result(p0, p1, self.clip_line_params(p0, p1))
+    }
 
-        // frustums are convex, so if both points are inside the frustum, we're done.
-        if in0 && in1 {
-            return ClipResult::Inside(*p0, *p1);
-        }
-        // Get the intersections for the line segment across all planes.
-        let mut isects: Vec<F> = (0..6)
-            .filter_map(|i| plane_segment_directed_intersection(&self.planes[i], p0, p1))
-            .filter_map(|t| {
-                if t >= F::zero() && t <= F::one() {
-                    Some(t)
-                } else {
-                    None
-                }
-            })
-            .collect();
+    /// Clip a 3d polyline against the frustum, returning the runs of
+    /// the polyline inside it.
+    ///
+    /// The polyline is split wherever it leaves the frustum, so a
+    /// polyline that leaves and re-enters gives multiple runs.
+    pub fn clip_polyline(&self, points: &[Point3<F>]) -> Vec<Vec<Point3<F>>> {
+        clip_polyline_by(points, |a, b| self.clip_line_params(a, b))
+    }
 
-        let cmp = |a: &F, b: &F| {
-            if a < b {
-                Ordering::Less
-            } else if a > b {
-                Ordering::Greater
+    fn clip_tagged_polygon(
+        &self,
+        poly: Vec<(Point3<F>, Option<usize>)>,
+    ) -> Vec<(Point3<F>, Option<usize>)> {
+        self.planes.iter().fold(poly, |poly, plane| {
+            if poly.is_empty() {
+                poly
             } else {
-                Ordering::Equal
+                clip_polygon_plane(plane, &poly)
+            }
+        })
+    }
+
+    /// Clip a convex, planar 3d polygon against the frustum, using
+    /// Sutherland-Hodgman against each plane in turn.
+    ///
+    /// Returns the vertices of the clipped polygon, in the same
+    /// winding order, or an empty list if the polygon is outside.
+    pub fn clip_polygon(&self, poly: &[Point3<F>]) -> Vec<Point3<F>> {
+        let tagged = poly.iter().map(|p| (*p, None)).collect();
+        self.clip_tagged_polygon(tagged)
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    /// Clip an indexed triangle list against the frustum.
+    ///
+    /// Returns a new vertex list and triangle index list. The input
+    /// vertices are kept at their original indices, followed by the
+    /// vertices created by clipping. Clipped triangles are
+    /// re-triangulated as fans, preserving winding order.
+    ///
+    /// Returns `None` if the index of a vertex created by clipping
+    /// does not fit in `I`, e.g. past 65535 for `u16`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index does not convert to `usize` or is out of
+    /// range for `vertices`.
+    pub fn clip_triangles<I>(
+        &self,
+        vertices: &[Point3<F>],
+        indices: &[I],
+    ) -> Option<(Vec<Point3<F>>, Vec<I>)>
+    where
+        I: Copy + TryInto<usize> + TryFrom<usize>,
+        <I as TryInto<usize>>::Error: Debug,
+    {
+        let mut out_vertices = vertices.to_vec();
+        let mut out_indices = Vec::with_capacity(indices.len());
+        let inside: Vec<bool> = vertices.iter().map(|v| self.is_point_in(v)).collect();
+
+        for tri in indices.chunks_exact(3) {
+            let idx: Vec<usize> = tri.iter().map(|&i| i.try_into().unwrap()).collect();
+            if idx.iter().all(|&i| inside[i]) {
+                out_indices.extend_from_slice(tri);
+                continue;
             }
-        };
-        isects.sort_by(cmp);
 
-        // If the first point is inside, we can find the first
-        // intersection and use that as the other clip point.
-        if in0 {
-            let min_t: F = isects[0];
-            return ClipResult::Partial(ClipResultPartial::Prefix, *p0, p0 + (p1 - p0) * min_t);
+            let poly = idx.iter().map(|&i| (vertices[i], Some(i))).collect();
+            let clipped = self.clip_tagged_polygon(poly);
+            if clipped.len() < 3 {
+                continue;
+            }
+            let fan: Vec<usize> = clipped
+                .iter()
+                .map(|(p, orig)| {
+                    orig.unwrap_or_else(|| {
+                        out_vertices.push(*p);
+                        out_vertices.len() - 1
+                    })
+                })
+                .collect();
+            for i in 1..fan.len() - 1 {
+                for &j in &[fan[0], fan[i], fan[i + 1]] {
+                    out_indices.push(j.try_into().ok()?);
+                }
+            }
         }
 
-        // If the second point inside, we can find the last
-        // intersection and use that as the other clip point.
-        if in1 {
-            let max_t: F = *isects.last().unwrap();
-            return ClipResult::Partial(ClipResultPartial::Suffix, p0 + (p1 - p0) * max_t, *p1);
+        Some((out_vertices, out_indices))
+    }
+
+    /// Classify a volume with the given center and, for each plane,
+    /// extent along the plane normal.
+    fn classify_extent<R: Fn(&Vector3<F>) -> F>(
+        &self,
+        center: &Point3<F>,
+        radius: R,
+    ) -> Containment {
+        let mut result = Containment::Inside;
+        for plane in &self.planes {
+            let n = plane.xyz();
+            let scale = n.norm();
+            let d = plane_distance(plane, center) / scale;
+            let r = radius(&n) / scale;
+            if d < -r {
+                return Containment::Outside;
+            }
+            if d < r {
+                result = Containment::Intersecting;
+            }
         }
+        result
+    }
 
-        // If both points are outside, then we need to find the
-        // intersection points that are on the frustum.
-        let eps = na::convert(1e-5);
-        let points_on_frustum: Vec<_> = isects
-            .iter()
-            .filter_map(|t| {
-                let p = p0 + (p1 - p0) * *t;
-                if self.is_point_in_or_on(&p, eps) {
-                    Some(p)
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        match points_on_frustum.len() {
-            2 => ClipResult::Partial(
-                ClipResultPartial::Infix,
-                points_on_frustum[0],
-                points_on_frustum[1],
-            ),
-            0 | 1 => ClipResult::Outside,
-            _ => panic!("Too many on-frustum intersections"),
+    /// Classify a sphere against the frustum.
+    pub fn classify_sphere(&self, center: &Point3<F>, radius: F) -> Containment {
+        self.classify_extent(center, |n| radius * n.norm())
+    }
+
+    /// Return the 8 corners of the frustum, from the intersections of
+    /// the planes, in (left, right) x (bottom, top) x (near, far)
+    /// order with the first varying fastest.
+    ///
+    /// Corners of degenerate frustums (e.g. with parallel planes
+    /// meeting at infinity) are returned at the origin.
+    pub fn corners(&self) -> [Point3<F>; 8] {
+        let mut corners = [Point3::origin(); 8];
+        for (i, c) in corners.iter_mut().enumerate() {
+            let p = [
+                &self.planes[i & 1],
+                &self.planes[2 + ((i >> 1) & 1)],
+                &self.planes[4 + ((i >> 2) & 1)],
+            ];
+            let m = Matrix3::from_rows(&[
+                p[0].xyz().transpose(),
+                p[1].xyz().transpose(),
+                p[2].xyz().transpose(),
+            ]);
+            let rhs = -Vector3::new(p[0][3], p[1][3], p[2][3]);
+            if let Some(x) = m.lu().solve(&rhs) {
+                *c = Point3::from(x);
+            }
         }
+        corners
     }
 
     /// Return the desired frustum plane.
@@ -179,3 +456,150 @@ impl<F: na::RealField> Frustum<F> {
         self.planes[idx as usize]
     }
 }
+
+impl<F: Scalar> Frustum<F> {
+    /// Classify an axis-aligned box against the frustum.
+    pub fn classify_aabb(&self, b: &Aabb<F>) -> Containment {
+        let h = b.dim() * na::convert::<f64, F>(0.5);
+        self.classify_extent(&b.center(), |n| n.abs().dot(&h))
+    }
+
+    /// Classify an oriented box against the frustum.
+    pub fn classify_obb(&self, b: &Obb<F>) -> Containment {
+        self.classify_extent(&b.center, |n| {
+            (0..3).fold(F::zero(), |acc, i| {
+                acc + b.half_widths[i] * n.dot(&b.axes[i]).abs()
+            })
+        })
+    }
+
+    /// Return the distances of the near and far planes from the eye,
+    /// along with the eye position.
+    ///
+    /// For frustums without an eye (e.g. orthographic), distances are
+    /// measured from the near plane.
+    fn depth_range(&self) -> (Point3<F>, F, F) {
+        let near = self.planes[FrustumPlane::Near as usize];
+        let n = near.xyz() / near.xyz().norm();
+        let sides = &self.planes[..3];
+        let m = Matrix3::from_rows(&[
+            sides[0].xyz().transpose(),
+            sides[1].xyz().transpose(),
+            sides[2].xyz().transpose(),
+        ]);
+        let rhs = -Vector3::new(sides[0][3], sides[1][3], sides[2][3]);
+        let eye = match m.lu().solve(&rhs) {
+            Some(e) if plane_distance(&near, &Point3::from(e)) < F::zero() => Point3::from(e),
+            // a point on the near plane
+            _ => Point3::from(-n * (near[3] / near.xyz().norm())),
+        };
+        let dist = |plane: &Vector4<F>| plane_distance(plane, &eye) / plane.xyz().norm();
+        (
+            eye,
+            -dist(&near),
+            dist(&self.planes[FrustumPlane::Far as usize]),
+        )
+    }
+
+    /// Return the distances from the eye of the boundaries between
+    /// `n` depth ranges, including the near and far distances.
+    ///
+    /// Logarithmic splits need a frustum with an eye in front of the
+    /// near plane; otherwise, they fall back to uniform splits. The
+    /// result is empty for `n == 0`.
+    pub fn split_distances(&self, n: usize, scheme: DepthSplit<F>) -> Vec<F> {
+        if n == 0 {
+            return vec![];
+        }
+        let (_, near, far) = self.depth_range();
+        let lambda = match scheme {
+            DepthSplit::Uniform => F::zero(),
+            DepthSplit::Logarithmic => F::one(),
+            DepthSplit::Practical(l) => l,
+        };
+        let lambda = if near > F::zero() { lambda } else { F::zero() };
+        (0..=n)
+            .map(|i| {
+                let f: F = na::convert(i as f64 / n as f64);
+                let uniform = near + (far - near) * f;
+                if lambda > F::zero() {
+                    let log = near * (far / near).powf(f);
+                    log * lambda + uniform * (F::one() - lambda)
+                } else {
+                    uniform
+                }
+            })
+            .collect()
+    }
+
+    /// Split the frustum into `n` consecutive depth ranges, from near
+    /// to far, using the given scheme for the boundaries.
+    ///
+    /// Boundary planes are parallel to the near plane.
+    pub fn split_depth(&self, n: usize, scheme: DepthSplit<F>) -> Vec<Frustum<F>> {
+        let (eye, _, _) = self.depth_range();
+        let near = self.planes[FrustumPlane::Near as usize];
+        let normal = near.xyz() / near.xyz().norm();
+        let offset = normal.dot(&eye.coords);
+        let dist = self.split_distances(n, scheme);
+
+        (0..n)
+            .map(|i| {
+                let mut planes = self.planes;
+                if i > 0 {
+                    planes[FrustumPlane::Near as usize] =
+                        Vector4::new(normal.x, normal.y, normal.z, -dist[i] - offset);
+                }
+                if i + 1 < n {
+                    planes[FrustumPlane::Far as usize] =
+                        Vector4::new(-normal.x, -normal.y, -normal.z, dist[i + 1] + offset);
+                }
+                Frustum { planes }
+            })
+            .collect()
+    }
+
+    /// Split the frustum into an `nx` by `ny` grid of screen-space
+    /// tiles, each covering the full depth range.
+    ///
+    /// Tile (i, j), counting from the left and bottom, is at index
+    /// j * nx + i.
+    pub fn split_tiles(&self, nx: usize, ny: usize) -> Vec<Frustum<F>> {
+        let c = self.corners();
+        let lerp = |a: &Point3<F>, b: &Point3<F>, t: F| a + (b - a) * t;
+        // point on the near (f = 0) or far (f = 1) face, at fractions
+        // (u, v) across the screen
+        let face = |f: usize, u: F, v: F| {
+            let bottom = lerp(&c[4 * f], &c[4 * f + 1], u);
+            let top = lerp(&c[4 * f + 2], &c[4 * f + 3], u);
+            lerp(&bottom, &top, v)
+        };
+        let frac = |i: usize, n: usize| -> F { na::convert(i as f64 / n as f64) };
+
+        let mut tiles = Vec::with_capacity(nx * ny);
+        for j in 0..ny {
+            for i in 0..nx {
+                let (u0, u1) = (frac(i, nx), frac(i + 1, nx));
+                let (v0, v1) = (frac(j, ny), frac(j + 1, ny));
+                let (um, vm) = ((u0 + u1) * na::convert(0.5), (v0 + v1) * na::convert(0.5));
+                let inside = na::center(&face(0, um, vm), &face(1, um, vm));
+
+                let side = |ua: F, va: F, ub: F, vb: F| {
+                    plane_from_points(
+                        &face(0, ua, va),
+                        &face(0, ub, vb),
+                        &face(1, ua, va),
+                        &inside,
+                    )
+                };
+                let mut planes = self.planes;
+                planes[FrustumPlane::Left as usize] = side(u0, v0, u0, v1);
+                planes[FrustumPlane::Right as usize] = side(u1, v0, u1, v1);
+                planes[FrustumPlane::Bottom as usize] = side(u0, v0, u1, v0);
+                planes[FrustumPlane::Top as usize] = side(u0, v1, u1, v1);
+                tiles.push(Frustum { planes });
+            }
+        }
+        tiles
+    }
+}
@@ -0,0 +1,414 @@
+//! Hatch fills for polygons, for pen-plotter style shading.
+//!
+//! Regions are given as a list of rings (closed polygons). Whether a
+//! point is filled is decided by the even-odd rule, so holes are
+//! given as additional rings inside the outer one, in any
+//! orientation.
+use crate::math::{point_in_polygon_even_odd, PointTest, Rect, Scalar};
+use na::{Point2, Vector2};
+use nalgebra as na;
+use ndarray::Array2;
+use std::collections::HashMap;
+
+/// A single layer of parallel hatch lines.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HatchLayer<F: Scalar> {
+    /// Angle of the lines, in radians counter-clockwise from the x-axis.
+    pub angle: F,
+
+    /// Distance between adjacent lines. Must be positive; a layer
+    /// with zero or negative spacing produces no lines.
+    pub spacing: F,
+}
+
+impl<F: Scalar> HatchLayer<F> {
+    pub fn new(angle: F, spacing: F) -> HatchLayer<F> {
+        HatchLayer { angle, spacing }
+    }
+}
+
+/// Rotate a point about the origin by the angle with the given
+/// (cos, sin).
+fn rotate<F: Scalar>(p: &Point2<F>, cs: (F, F)) -> Point2<F> {
+    Point2::new(cs.0 * p.x - cs.1 * p.y, cs.1 * p.x + cs.0 * p.y)
+}
+
+/// Return the sorted x-coordinates where the horizontal line at `y`
+/// crosses the rings.
+fn scanline_crossings<F: Scalar>(rings: &[Vec<Point2<F>>], y: F) -> Vec<F> {
+    let mut xs = vec![];
+    for ring in rings {
+        let n = ring.len();
+        for i in 0..n {
+            let (a, b) = (ring[i], ring[(i + 1) % n]);
+            // half-open rule, so vertices on the line are counted once
+            if (a.y > y) != (b.y > y) {
+                xs.push(a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x));
+            }
+        }
+    }
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    xs
+}
+
+/// Call `f` with the index and the inside spans of each hatch line
+/// of the layer, in the layer's rotated frame where the lines are
+/// horizontal.
+///
+/// Lines are aligned to a global grid, so that neighboring regions
+/// hatched with the same layer line up.
+fn for_each_span<F: Scalar, T: FnMut(i64, F, &[F])>(
+    rings: &[Vec<Point2<F>>],
+    layer: &HatchLayer<F>,
+    mut f: T,
+) {
+    if layer.spacing <= F::zero() {
+        return;
+    }
+    let inv = (layer.angle.cos(), -layer.angle.sin());
+    let rotated: Vec<Vec<Point2<F>>> = rings
+        .iter()
+        .map(|r| r.iter().map(|p| rotate(p, inv)).collect())
+        .collect();
+
+    let ys = rotated.iter().flatten().map(|p| p.y);
+    let (y0, y1) = match ys.clone().reduce(|a, b| a.min(b)) {
+        Some(y0) => (y0, ys.reduce(|a, b| a.max(b)).unwrap()),
+        None => return,
+    };
+
+    let k0: f64 = (y0 / layer.spacing).ceil().into();
+    let k1: f64 = (y1 / layer.spacing).floor().into();
+    let (k0, k1) = (k0 as i64, k1 as i64);
+    for k in k0..=k1 {
+        let y = layer.spacing * na::convert(k as f64);
+        let xs = scanline_crossings(&rotated, y);
+        f(k, y, &xs);
+    }
+}
+
+/// Fill the region with parallel lines.
+///
+/// Each line is returned as a two-point polyline.
+pub fn hatch<F: Scalar>(rings: &[Vec<Point2<F>>], layer: &HatchLayer<F>) -> Vec<Vec<Point2<F>>> {
+    let fwd = (layer.angle.cos(), layer.angle.sin());
+    let mut lines = vec![];
+    for_each_span(rings, layer, |_, y, xs| {
+        for span in xs.chunks_exact(2) {
+            if span[1] > span[0] {
+                lines.push(vec![
+                    rotate(&Point2::new(span[0], y), fwd),
+                    rotate(&Point2::new(span[1], y), fwd),
+                ]);
+            }
+        }
+    });
+    lines
+}
+
+/// Fill the region with each of the hatch layers in turn.
+pub fn multi_hatch<F: Scalar>(
+    rings: &[Vec<Point2<F>>],
+    layers: &[HatchLayer<F>],
+) -> Vec<Vec<Point2<F>>> {
+    layers.iter().flat_map(|l| hatch(rings, l)).collect()
+}
+
+/// Fill the region with `n` layers of lines, with angles evenly
+/// spread over a half-turn starting at `layer.angle`.
+///
+/// `n = 2` gives conventional perpendicular cross-hatching.
+pub fn cross_hatch<F: Scalar>(
+    rings: &[Vec<Point2<F>>],
+    layer: &HatchLayer<F>,
+    n: usize,
+) -> Vec<Vec<Point2<F>>> {
+    let layers: Vec<_> = (0..n)
+        .map(|i| {
+            let offset = F::pi() * na::convert(i as f64 / n as f64);
+            HatchLayer::new(layer.angle + offset, layer.spacing)
+        })
+        .collect();
+    multi_hatch(rings, &layers)
+}
+
+/// Return the base-2 radical inverse of `k`, in [0, 1).
+///
+/// Thresholding lines on this value keeps evenly spread subsets of
+/// lines for any density.
+fn radical_inverse(k: u32) -> f64 {
+    k.reverse_bits() as f64 / (1u64 << 32) as f64
+}
+
+/// Fill the region with parallel lines whose local spacing is
+/// modulated by a density function.
+///
+/// `layer.spacing` is the spacing at full density. Each line is kept
+/// only where `density` (expected in [0, 1]) exceeds a per-line
+/// threshold, so a density of 0.5 gives roughly twice the spacing,
+/// and 0 gives no lines. The density is sampled along each line
+/// every `step` units, which must be positive; otherwise no lines are
+/// returned.
+pub fn density_hatch<F: Scalar, D: Fn(&Point2<F>) -> F>(
+    rings: &[Vec<Point2<F>>],
+    layer: &HatchLayer<F>,
+    step: F,
+    density: D,
+) -> Vec<Vec<Point2<F>>> {
+    if step <= F::zero() {
+        return vec![];
+    }
+    let fwd = (layer.angle.cos(), layer.angle.sin());
+    let mut lines = vec![];
+    for_each_span(rings, layer, |k, y, xs| {
+        let threshold: F = na::convert(radical_inverse(k as u32));
+        let on = |x: F| density(&rotate(&Point2::new(x, y), fwd)) > threshold;
+
+        for span in xs.chunks_exact(2) {
+            let (x0, x1) = (span[0], span[1]);
+            let n: f64 = ((x1 - x0) / step).ceil().into();
+            let n = n.max(1.0) as usize;
+            let mut start = None;
+            for i in 0..=n {
+                let x = x0 + (x1 - x0) * na::convert(i as f64 / n as f64);
+                match (start, on(x)) {
+                    (None, true) => start = Some(x),
+                    (Some(s), false) => {
+                        lines.push(vec![
+                            rotate(&Point2::new(s, y), fwd),
+                            rotate(&Point2::new(x, y), fwd),
+                        ]);
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(s) = start {
+                if x1 > s {
+                    lines.push(vec![
+                        rotate(&Point2::new(s, y), fwd),
+                        rotate(&Point2::new(x1, y), fwd),
+                    ]);
+                }
+            }
+        }
+    });
+    lines
+}
+
+/// A grayscale image stretched over a rectangle, for use as a hatch
+/// density.
+///
+/// The array is indexed by (y, x), as with `read_rgba_image_to_array`.
+/// Darker values give denser hatching.
+pub struct DensityImage<F: Scalar> {
+    pub values: Array2<f32>,
+    pub rect: Rect<F>,
+}
+
+impl<F: Scalar> DensityImage<F> {
+    pub fn new(values: Array2<f32>, rect: Rect<F>) -> DensityImage<F> {
+        DensityImage { values, rect }
+    }
+
+    /// Return the density at a point, from bilinear interpolation of
+    /// the image values, as 1 - value.
+    pub fn density(&self, p: &Point2<F>) -> F {
+        let (h, w) = self.values.dim();
+        if h == 0 || w == 0 {
+            return F::zero();
+        }
+        let d = self.rect.dim();
+        let u: f64 = ((p.x - self.rect.p[0].x) / d.x).into();
+        let v: f64 = ((p.y - self.rect.p[0].y) / d.y).into();
+        let u = (u * w as f64 - 0.5).max(0.0).min((w - 1) as f64);
+        let v = (v * h as f64 - 0.5).max(0.0).min((h - 1) as f64);
+        let (x0, y0) = (u.floor() as usize, v.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+        let (fu, fv) = ((u - x0 as f64) as f32, (v - y0 as f64) as f32);
+
+        let top = self.values[[y0, x0]] * (1.0 - fu) + self.values[[y0, x1]] * fu;
+        let bottom = self.values[[y1, x0]] * (1.0 - fu) + self.values[[y1, x1]] * fu;
+        na::convert(1.0 - (top * (1.0 - fv) + bottom * fv) as f64)
+    }
+}
+
+/// Return the distance from `p` to the segment (a, b).
+fn segment_distance<F: Scalar>(p: &Point2<F>, a: &Point2<F>, b: &Point2<F>) -> F {
+    let ab = b - a;
+    let l2 = ab.norm_squared();
+    let t = if l2 > F::zero() {
+        ((p - a).dot(&ab) / l2).max(F::zero()).min(F::one())
+    } else {
+        F::zero()
+    };
+    (p - (a + ab * t)).norm()
+}
+
+/// Fill the region with contour-parallel (concentric) loops, spaced
+/// `spacing` apart, starting `spacing` in from the boundary.
+///
+/// Contours are traced on a sampled distance field with cells of size
+/// `resolution`, which should be a fraction of the spacing. The
+/// boundary itself is not included. Both `spacing` and `resolution`
+/// must be positive; otherwise no loops are returned.
+pub fn contour_hatch<F: Scalar>(
+    rings: &[Vec<Point2<F>>],
+    spacing: F,
+    resolution: F,
+) -> Vec<Vec<Point2<F>>> {
+    let points: Vec<_> = rings.iter().flatten().cloned().collect();
+    if points.is_empty() || spacing <= F::zero() || resolution <= F::zero() {
+        return vec![];
+    }
+    let lo = points.iter().fold(points[0], |a, b| a.inf(b));
+    let hi = points.iter().fold(points[0], |a, b| a.sup(b));
+
+    // pad by a cell, so that every contour closes within the grid
+    let origin = lo - Vector2::new(resolution, resolution);
+    let nx: f64 = ((hi.x - lo.x) / resolution).ceil().into();
+    let ny: f64 = ((hi.y - lo.y) / resolution).ceil().into();
+    let (nx, ny) = (nx as usize + 3, ny as usize + 3);
+
+    // signed distance field, positive inside
+    let mut field = Array2::from_elem((nx, ny), F::zero());
+    let mut max_dist = F::zero();
+    for ((ix, iy), d) in field.indexed_iter_mut() {
+        let p = origin
+            + Vector2::new(
+                resolution * na::convert(ix as f64),
+                resolution * na::convert(iy as f64),
+            );
+        let mut dist = F::max_value();
+        for ring in rings {
+            let n = ring.len();
+            for i in 0..n {
+                dist = dist.min(segment_distance(&p, &ring[i], &ring[(i + 1) % n]));
+            }
+        }
+        let inside = rings
+            .iter()
+            .filter(|r| point_in_polygon_even_odd(&p, r) == PointTest::Inside)
+            .count()
+            % 2
+            == 1;
+        *d = if inside { dist } else { -dist };
+        max_dist = max_dist.max(*d);
+    }
+
+    let mut contours = vec![];
+    let mut level = spacing;
+    while level < max_dist {
+        contours.extend(iso_contours(&field, level, &origin, resolution));
+        level += spacing;
+    }
+    contours
+}
+
+/// Key for a grid edge: the lower node and the axis (0 = x, 1 = y).
+type EdgeKey = (usize, usize, u8);
+
+/// Extract the iso-lines of a sampled field at `level` using marching
+/// squares, joined into polylines. Closed loops repeat their first
+/// point at the end.
+fn iso_contours<F: Scalar>(
+    field: &Array2<F>,
+    level: F,
+    origin: &Point2<F>,
+    h: F,
+) -> Vec<Vec<Point2<F>>> {
+    let (nx, ny) = field.dim();
+    let node = |ix: usize, iy: usize| field[[ix, iy]] - level;
+
+    // position of the level crossing on an edge
+    let crossing = |(ix, iy, axis): EdgeKey| -> Point2<F> {
+        let (jx, jy) = if axis == 0 {
+            (ix + 1, iy)
+        } else {
+            (ix, iy + 1)
+        };
+        let (a, b) = (node(ix, iy), node(jx, jy));
+        let t = a / (a - b);
+        let fx: F = na::convert(ix as f64);
+        let fy: F = na::convert(iy as f64);
+        let p = Point2::new(fx, fy)
+            + if axis == 0 {
+                Vector2::new(t, F::zero())
+            } else {
+                Vector2::new(F::zero(), t)
+            };
+        origin + p.coords * h
+    };
+
+    let mut segments: Vec<(EdgeKey, EdgeKey)> = vec![];
+    for ix in 0..nx - 1 {
+        for iy in 0..ny - 1 {
+            let v = [
+                node(ix, iy),
+                node(ix + 1, iy),
+                node(ix + 1, iy + 1),
+                node(ix, iy + 1),
+            ];
+            // edges in order: bottom, right, top, left
+            let edges = [(ix, iy, 0), (ix + 1, iy, 1), (ix, iy + 1, 0), (ix, iy, 1)];
+            let crossed: Vec<usize> = (0..4)
+                .filter(|&i| (v[i] > F::zero()) != (v[(i + 1) % 4] > F::zero()))
+                .collect();
+            match crossed.len() {
+                2 => segments.push((edges[crossed[0]], edges[crossed[1]])),
+                4 => {
+                    // saddle: resolve using the cell center
+                    let center = (v[0] + v[1] + v[2] + v[3]) * na::convert(0.25);
+                    if (center > F::zero()) == (v[0] > F::zero()) {
+                        segments.push((edges[0], edges[1]));
+                        segments.push((edges[2], edges[3]));
+                    } else {
+                        segments.push((edges[0], edges[3]));
+                        segments.push((edges[1], edges[2]));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // join segments sharing grid edges into polylines
+    let mut adjacent: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+    for (i, (a, b)) in segments.iter().enumerate() {
+        adjacent.entry(*a).or_default().push(i);
+        adjacent.entry(*b).or_default().push(i);
+    }
+    let mut used = vec![false; segments.len()];
+    let mut polylines = vec![];
+
+    // follow the chain of unused segments from an edge
+    let walk = |start: EdgeKey, used: &mut Vec<bool>| -> Vec<EdgeKey> {
+        let mut chain = vec![start];
+        let mut curr = start;
+        while let Some(&si) = adjacent[&curr].iter().find(|&&si| !used[si]) {
+            used[si] = true;
+            let (a, b) = segments[si];
+            curr = if a == curr { b } else { a };
+            chain.push(curr);
+        }
+        chain
+    };
+
+    // open chains start at edges with a single segment, so walk
+    // those first.
+    let mut starts: Vec<EdgeKey> = adjacent
+        .iter()
+        .filter(|(_, s)| s.len() == 1)
+        .map(|(k, _)| *k)
+        .collect();
+    starts.sort_unstable();
+    starts.extend(segments.iter().map(|s| s.0));
+    for start in starts {
+        if adjacent[&start].iter().all(|&si| used[si]) {
+            continue;
+        }
+        let chain = walk(start, &mut used);
+        polylines.push(chain.into_iter().map(crossing).collect());
+    }
+    polylines
+}
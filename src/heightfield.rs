@@ -0,0 +1,175 @@
+//! Meshes, scan lines and ridge-line plots from 2D height maps.
+//!
+//! Height maps are indexed [[row, column]], like the arrays from
+//! `read_rgba_image_to_array`. In 3D, columns run along x, rows along
+//! z, and heights along y, matching `Mesh::plane_grid`.
+use crate::math::{Rect, Scalar};
+use crate::mesh::Mesh;
+use na::{Point2, Point3, Vector2};
+use nalgebra as na;
+use ndarray::{ArrayBase, Data, Ix2};
+
+/// Create a surface mesh from a height map, spanning `size` (width
+/// along x, depth along z) centered on the origin, with heights
+/// multiplied by `height_scale`.
+///
+/// Vertex (i, j), for column i and row j, is at index j * ncols + i,
+/// so the rows and columns can be drawn with `add_grid_lines`. The
+/// mesh has smooth normals and UVs. The mesh is empty if the map has
+/// fewer than two rows or columns.
+pub fn heightfield_mesh<F: Scalar, S: Data<Elem = F>>(
+    heights: &ArrayBase<S, Ix2>,
+    size: &Vector2<F>,
+    height_scale: F,
+) -> Mesh<F> {
+    let (rows, cols) = heights.dim();
+    if rows < 2 || cols < 2 {
+        return Mesh::default();
+    }
+    let mut mesh = Mesh::plane_grid(size.x, size.y, cols - 1, rows - 1);
+    for ((j, i), h) in heights.indexed_iter() {
+        mesh.positions[j * cols + i].y = *h * height_scale;
+    }
+    mesh.compute_normals();
+    mesh
+}
+
+/// Return a 3D polyline along each row of a height map, in the same
+/// coordinates as `heightfield_mesh`.
+pub fn heightfield_scanlines<F: Scalar, S: Data<Elem = F>>(
+    heights: &ArrayBase<S, Ix2>,
+    size: &Vector2<F>,
+    height_scale: F,
+) -> Vec<Vec<Point3<F>>> {
+    let (rows, cols) = heights.dim();
+    let half: F = na::convert(0.5);
+    let coord = |i: usize, n: usize, extent: F| {
+        let t: F = na::convert(i as f64 / (n.max(2) - 1) as f64);
+        (t - half) * extent
+    };
+    (0..rows)
+        .map(|j| {
+            let z = coord(j, rows, size.y);
+            (0..cols)
+                .map(|i| Point3::new(coord(i, cols, size.x), heights[[j, i]] * height_scale, z))
+                .collect()
+        })
+        .collect()
+}
+
+/// The upper envelope of the rows drawn so far over one interval
+/// between columns, in page coordinates with y down.
+///
+/// Points are (t, y) for the parameter t in [0, 1] across the
+/// interval. As the minimum of linear functions, the envelope is
+/// concave, so a new segment can only be visible over a single range.
+struct Horizon<F: Scalar> {
+    points: Vec<(F, F)>,
+}
+
+impl<F: Scalar> Horizon<F> {
+    /// Add the segment from y0 at t = 0 to y1 at t = 1, returning the
+    /// range of t over which it is above the horizon, if any.
+    fn add(&mut self, y0: F, y1: F) -> Option<(F, F)> {
+        let (zero, one) = (F::zero(), F::one());
+        let line = |t: F| y0 + (y1 - y0) * t;
+        if self.points.is_empty() {
+            self.points = vec![(zero, y0), (one, y1)];
+            return Some((zero, one));
+        }
+
+        // signed distance below the horizon, negative where visible
+        let d: Vec<F> = self.points.iter().map(|&(t, y)| line(t) - y).collect();
+        let mut points = Vec::with_capacity(self.points.len() + 2);
+        let mut visible: Option<(F, F)> = None;
+        let mut extend = |t: F| {
+            visible = Some(match visible {
+                Some((a, _)) => (a, t),
+                None => (t, t),
+            })
+        };
+        for k in 0..self.points.len() {
+            let (t, y) = self.points[k];
+            if k > 0 && (d[k - 1] < zero) != (d[k] < zero) {
+                // the segment crosses the horizon
+                let (t0, _) = self.points[k - 1];
+                let s = d[k - 1] / (d[k - 1] - d[k]);
+                let tc = t0 + (t - t0) * s;
+                points.push((tc, line(tc)));
+                extend(tc);
+            }
+            if d[k] < zero {
+                points.push((t, line(t)));
+                extend(t);
+            } else {
+                points.push((t, y));
+            }
+        }
+        self.points = points;
+        visible.filter(|(a, b)| a < b)
+    }
+}
+
+/// Render a height map as a ridge-line ("joy division") plot.
+///
+/// Row 0 is the front row, with its baseline at the bottom of the
+/// rect, and each following row is placed higher up and behind the
+/// rows before it. Columns span the width of the rect. Points rise
+/// above their baseline by `amplitude` times their height, and each
+/// row hides the parts of later rows below it. Returns the visible
+/// pieces as polylines.
+///
+/// To put the first row of an image at the top of the plot, reverse
+/// the rows, e.g. with `heights.slice(s![..;-1, ..])`.
+pub fn ridge_lines<F: Scalar, S: Data<Elem = F>>(
+    heights: &ArrayBase<S, Ix2>,
+    rect: &Rect<F>,
+    amplitude: F,
+) -> Vec<Vec<Point2<F>>> {
+    let (rows, cols) = heights.dim();
+    if cols < 2 {
+        return vec![];
+    }
+    let d = rect.dim();
+    let fraction = |i: usize, n: usize| -> F { na::convert(i as f64 / (n.max(2) - 1) as f64) };
+    let mut horizons: Vec<Horizon<F>> = (1..cols).map(|_| Horizon { points: vec![] }).collect();
+
+    let mut lines = vec![];
+    for j in 0..rows {
+        let baseline = rect.p[1].y - d.y * fraction(j, rows);
+        let point = |i: usize| {
+            Point2::new(
+                rect.p[0].x + d.x * fraction(i, cols),
+                baseline - amplitude * heights[[j, i]],
+            )
+        };
+        let mut curr: Vec<Point2<F>> = vec![];
+        for (i, horizon) in horizons.iter_mut().enumerate() {
+            let (a, b) = (point(i), point(i + 1));
+            match horizon.add(a.y, b.y) {
+                Some((t0, t1)) => {
+                    if t0 > F::zero() && !curr.is_empty() {
+                        lines.push(std::mem::take(&mut curr));
+                    }
+                    let p = |t: F| a + (b - a) * t;
+                    if curr.is_empty() {
+                        curr.push(p(t0));
+                    }
+                    curr.push(p(t1));
+                    if t1 < F::one() {
+                        lines.push(std::mem::take(&mut curr));
+                    }
+                }
+                None => {
+                    if !curr.is_empty() {
+                        lines.push(std::mem::take(&mut curr));
+                    }
+                }
+            }
+        }
+        if !curr.is_empty() {
+            lines.push(curr);
+        }
+    }
+    lines
+}
@@ -0,0 +1,232 @@
+//! Hidden-line removal for edges of triangle meshes.
+//!
+//! Occlusion is computed in normalized device coordinates, where
+//! projected triangles stay planar and segments stay straight, so the
+//! part of a segment hidden by a triangle is a single interval along
+//! the segment.
+use crate::camera::ndc_to_rect;
+use crate::math::Scalar;
+use crate::projector::Projector;
+use na::{Point2, Point3, Vector3};
+use nalgebra as na;
+use num_integer::Roots;
+use std::convert::TryInto;
+use std::fmt::Debug;
+
+/// Options for `visible_segments`.
+#[derive(Clone, Copy, Debug)]
+pub struct HiddenLineOptions<F: Scalar> {
+    /// A segment is only hidden by a triangle when it is farther than
+    /// this in normalized device depth. This keeps edges from being
+    /// hidden by the faces they belong to.
+    pub depth_bias: F,
+
+    /// Visible pieces shorter than this fraction of their segment
+    /// are dropped.
+    pub min_fraction: F,
+}
+
+impl<F: Scalar> Default for HiddenLineOptions<F> {
+    fn default() -> Self {
+        HiddenLineOptions {
+            depth_bias: na::convert(1e-6),
+            min_fraction: na::convert(1e-6),
+        }
+    }
+}
+
+/// Intersect the interval [lo, hi] with the set where the affine
+/// function with values f0 at t = 0 and f1 at t = 1 is positive.
+fn clip_interval<F: Scalar>(lo: F, hi: F, f0: F, f1: F) -> (F, F) {
+    let df = f1 - f0;
+    if df == F::zero() {
+        return if f0 > F::zero() {
+            (lo, hi)
+        } else {
+            (F::one(), F::zero())
+        };
+    }
+    let t = -f0 / df;
+    if df > F::zero() {
+        (lo.max(t), hi)
+    } else {
+        (lo, hi.min(t))
+    }
+}
+
+fn cross<F: Scalar>(o: &Point3<F>, a: &Point3<F>, b: &Point3<F>) -> F {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+/// Return the interval of parameters along the segment (p0, p1) that
+/// is hidden by the triangle, if any. All points are in normalized
+/// device coordinates.
+fn hidden_interval<F: Scalar>(
+    p0: &Point3<F>,
+    p1: &Point3<F>,
+    tri: &[Point3<F>; 3],
+    bias: F,
+) -> Option<(F, F)> {
+    let area = cross(&tri[0], &tri[1], &tri[2]);
+    if area.abs() <= F::default_epsilon() {
+        return None;
+    }
+    let sign = area.signum();
+    let (mut lo, mut hi) = (F::zero(), F::one());
+
+    // inside each edge of the projected triangle
+    for i in 0..3 {
+        let (a, b) = (&tri[i], &tri[(i + 1) % 3]);
+        let (lo_i, hi_i) = clip_interval(lo, hi, cross(a, b, p0) * sign, cross(a, b, p1) * sign);
+        lo = lo_i;
+        hi = hi_i;
+        if lo >= hi {
+            return None;
+        }
+    }
+
+    // behind the plane of the triangle, extrapolating the depth of
+    // the triangle with barycentric coordinates
+    let depth = |p: &Point3<F>| {
+        let w1 = cross(&tri[2], &tri[0], p) / area;
+        let w2 = cross(&tri[0], &tri[1], p) / area;
+        let w0 = F::one() - w1 - w2;
+        tri[0].z * w0 + tri[1].z * w1 + tri[2].z * w2
+    };
+    let (lo, hi) = clip_interval(lo, hi, p0.z - depth(p0) - bias, p1.z - depth(p1) - bias);
+    if lo < hi {
+        Some((lo, hi))
+    } else {
+        None
+    }
+}
+
+/// A uniform grid over the [-1, 1] square of normalized device
+/// coordinates, holding triangles by bounding box.
+struct TriangleGrid {
+    n: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl TriangleGrid {
+    fn cell_range<F: Scalar>(&self, lo: F, hi: F) -> (usize, usize) {
+        let cell = |x: F| {
+            let x: f64 = x.into();
+            (((x + 1.0) * 0.5 * self.n as f64).floor().max(0.0) as usize).min(self.n - 1)
+        };
+        (cell(lo), cell(hi))
+    }
+
+    fn new<F: Scalar>(tris: &[[Point3<F>; 3]]) -> TriangleGrid {
+        let n = (tris.len().sqrt() / 2).max(1);
+        let mut grid = TriangleGrid {
+            n,
+            cells: vec![vec![]; n * n],
+        };
+        for (ti, tri) in tris.iter().enumerate() {
+            let lo = tri[0].inf(&tri[1]).inf(&tri[2]);
+            let hi = tri[0].sup(&tri[1]).sup(&tri[2]);
+            let (x0, x1) = grid.cell_range(lo.x, hi.x);
+            let (y0, y1) = grid.cell_range(lo.y, hi.y);
+            for ix in x0..=x1 {
+                for iy in y0..=y1 {
+                    grid.cells[ix * n + iy].push(ti);
+                }
+            }
+        }
+        grid
+    }
+}
+
+/// Return the visible parts of the line segments of a mesh, projected
+/// onto the page.
+///
+/// `vertices` and `triangles` are an indexed triangle list, as built
+/// by `add_box`, and `lines` holds pairs of vertex indices for the
+/// edges to draw, as built by `add_linear_index`. All triangles
+/// occlude, whichever way they face. Each visible piece is returned
+/// as a two-point polyline.
+pub fn visible_segments<F, I>(
+    projector: &Projector<F>,
+    vertices: &[Vector3<F>],
+    triangles: &[I],
+    lines: &[I],
+    opts: &HiddenLineOptions<F>,
+) -> Vec<Vec<Point2<F>>>
+where
+    F: Scalar,
+    I: Copy + TryInto<usize>,
+    <I as TryInto<usize>>::Error: Debug,
+{
+    let vertex = |i: I| -> Point3<F> { Point3::from(vertices[i.try_into().unwrap()]) };
+    let frustum = projector.frustum();
+
+    // clip the triangles to the view and fan-triangulate them in
+    // normalized device coordinates
+    let mut tris: Vec<[Point3<F>; 3]> = vec![];
+    for t in triangles.chunks_exact(3) {
+        let clipped = frustum.clip_polygon(&[vertex(t[0]), vertex(t[1]), vertex(t[2])]);
+        let ndc: Vec<_> = clipped.iter().map(|p| projector.to_ndc(p)).collect();
+        for i in 1..ndc.len().saturating_sub(1) {
+            tris.push([ndc[0], ndc[i], ndc[i + 1]]);
+        }
+    }
+    let grid = TriangleGrid::new(&tris);
+    let min_depth: Vec<F> = tris
+        .iter()
+        .map(|t| t[0].z.min(t[1].z).min(t[2].z))
+        .collect();
+
+    let mut visited = vec![usize::MAX; tris.len()];
+    let mut segments = vec![];
+    let mut seg_id = 0;
+    for l in lines.chunks_exact(2) {
+        for piece in projector.clip_polyline(&[vertex(l[0]), vertex(l[1])]) {
+            let (p0, p1) = (projector.to_ndc(&piece[0]), projector.to_ndc(&piece[1]));
+            let max_z = p0.z.max(p1.z);
+
+            // collect the hidden intervals from triangles overlapping
+            // the segment's bounding box
+            let (x0, x1) = grid.cell_range(p0.x.min(p1.x), p0.x.max(p1.x));
+            let (y0, y1) = grid.cell_range(p0.y.min(p1.y), p0.y.max(p1.y));
+            let mut hidden = vec![];
+            for ix in x0..=x1 {
+                for iy in y0..=y1 {
+                    for &ti in &grid.cells[ix * grid.n + iy] {
+                        if visited[ti] == seg_id {
+                            continue;
+                        }
+                        visited[ti] = seg_id;
+                        // the triangle is entirely behind the segment
+                        if min_depth[ti] + opts.depth_bias >= max_z {
+                            continue;
+                        }
+                        if let Some(h) = hidden_interval(&p0, &p1, &tris[ti], opts.depth_bias) {
+                            hidden.push(h);
+                        }
+                    }
+                }
+            }
+            seg_id += 1;
+
+            // emit the gaps between the merged hidden intervals
+            hidden.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            let rect = projector.rect();
+            let mut emit = |a: F, b: F| {
+                if b - a > opts.min_fraction {
+                    let p = |t: F| ndc_to_rect(&(p0 + (p1 - p0) * t).xy(), rect);
+                    segments.push(vec![p(a), p(b)]);
+                }
+            };
+            let mut t = F::zero();
+            for (lo, hi) in hidden {
+                if lo > t {
+                    emit(t, lo);
+                }
+                t = t.max(hi);
+            }
+            emit(t, F::one());
+        }
+    }
+    segments
+}
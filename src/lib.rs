@@ -1,17 +1,29 @@
+pub mod camera;
 mod color;
 mod common;
+pub mod convex_volume;
 pub mod easing;
 mod fn_gen;
 pub mod frustum;
+pub mod hatch;
+pub mod heightfield;
+pub mod hidden_line;
 mod image_pack;
 mod image_util;
 mod math;
+pub mod mesh;
 pub mod models;
+pub mod optics;
+pub mod plot_opt;
 mod poisson;
+pub mod projector;
 mod random;
 mod spatial_hash;
 pub mod svg;
-//mod dual_contour;
+pub mod tsp;
+#[allow(dead_code, unused_imports)]
+#[rustfmt::skip]
+mod dual_contour;
 
 pub mod midi;
 
@@ -26,16 +38,38 @@ pub use image_pack::{
 
 pub use crate::svg::{polygon_to_node, polyline_to_node};
 pub use image_util::read_rgba_image_to_array;
-pub use math::{clip_line, clip_polyline, ClipResult, Rect};
+pub use math::{
+    centroid, is_convex, is_simple, orientation, perimeter, point_in_polygon_even_odd,
+    point_in_polygon_winding, self_intersections, signed_area, winding_number, Orientation,
+};
+pub use math::{clip_line, clip_polyline, Aabb, ClipResult, Obb, Rect, Transform2};
+pub use math::{
+    closest_point_on_segment, closest_point_on_triangle, closest_points_segments,
+    point_segment_distance, ray_aabb_intersect, ray_plane_intersect, ray_triangle_intersect,
+    ClosestPoints, TriangleHit,
+};
+pub use math::{
+    fresnel_reflectance, reflect_dir, refract_dir, refract_dir_3d, Refraction, ToArray,
+};
 pub use math::{implicit_ray_intersect_2d, line_intersect_2d, orient_2d, PointTest};
-pub use math::{refract_dir, ToArray};
-pub use models::add_box;
+pub use mesh::Mesh;
+pub use models::{
+    add_box, add_grid_lines, add_linear_index, add_strips, add_triangle_edges, GridLines,
+};
+pub use optics::OpticalScene;
+pub use plot_opt::optimize_paths;
 pub use poisson::PoissonSampling;
+pub use projector::Projector;
 pub use spatial_hash::SpatialHash2D;
 
+pub use camera::Camera;
 pub use color::{parse_hex_srgb, parse_hex_srgba};
+pub use convex_volume::ConvexVolume;
 pub use fn_gen::gen_dated_filenames;
-pub use frustum::Frustum;
+pub use frustum::{Containment, DepthSplit, Frustum};
 pub use math::find_root;
+#[cfg(feature = "lapack")]
+pub use math::LapackScalar;
+pub use math::Scalar;
 pub use random::{random_quat, random_unit_vector};
 //pub use dual_contour::{CellClass, QuadTree};
@@ -0,0 +1,262 @@
+//! 3D segment, ray, and primitive intersection utilities.
+use crate::math::{Aabb, Scalar};
+use na::{Point3, Vector3, Vector4};
+use nalgebra as na;
+
+/// Closest points between two segments, along with the segment
+/// parameters at which they occur.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClosestPoints<F: Scalar> {
+    /// Parameter along the first segment, in [0, 1].
+    pub s: F,
+    /// Parameter along the second segment, in [0, 1].
+    pub t: F,
+    pub p: Point3<F>,
+    pub q: Point3<F>,
+}
+
+impl<F: Scalar> ClosestPoints<F> {
+    /// Return the distance between the closest points.
+    pub fn distance(&self) -> F {
+        (self.q - self.p).norm()
+    }
+}
+
+/// Intersection of a ray with a triangle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TriangleHit<F: Scalar> {
+    /// Distance along the ray, in units of the ray direction.
+    pub t: F,
+    /// Barycentric coordinates of the hit with respect to the second
+    /// and third vertices.
+    pub u: F,
+    pub v: F,
+}
+
+fn clamp01<F: Scalar>(x: F) -> F {
+    x.max(F::zero()).min(F::one())
+}
+
+/// Return the closest point to `p` on the segment (a, b), along with
+/// its parameter along the segment.
+pub fn closest_point_on_segment<F: Scalar>(
+    p: &Point3<F>,
+    a: &Point3<F>,
+    b: &Point3<F>,
+) -> (F, Point3<F>) {
+    let ab = b - a;
+    let l2 = ab.norm_squared();
+    if l2 <= F::default_epsilon() {
+        return (F::zero(), *a);
+    }
+    let t = clamp01((p - a).dot(&ab) / l2);
+    (t, a + ab * t)
+}
+
+/// Return the distance from `p` to the segment (a, b).
+pub fn point_segment_distance<F: Scalar>(p: &Point3<F>, a: &Point3<F>, b: &Point3<F>) -> F {
+    let (_, c) = closest_point_on_segment(p, a, b);
+    (p - c).norm()
+}
+
+/// Return the closest point to `p` on the triangle (a, b, c).
+///
+/// Follows Ericson, Real-Time Collision Detection, 5.1.5.
+pub fn closest_point_on_triangle<F: Scalar>(
+    p: &Point3<F>,
+    a: &Point3<F>,
+    b: &Point3<F>,
+    c: &Point3<F>,
+) -> Point3<F> {
+    let zero = F::zero();
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= zero && d2 <= zero {
+        return *a;
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= zero && d4 <= d3 {
+        return *b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= zero && d1 >= zero && d3 <= zero {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= zero && d5 <= d6 {
+        return *c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= zero && d2 >= zero && d6 <= zero {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= zero && d4 >= d3 && d5 >= d6 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = va + vb + vc;
+    if denom <= zero {
+        // degenerate triangle, so use the nearest edge
+        let edges = [(a, b), (b, c), (c, a)];
+        let points = edges
+            .iter()
+            .map(|(u, v)| closest_point_on_segment(p, u, v).1);
+        return points.fold(*a, |best, q| {
+            if (q - p).norm_squared() < (best - p).norm_squared() {
+                q
+            } else {
+                best
+            }
+        });
+    }
+    a + ab * (vb / denom) + ac * (vc / denom)
+}
+
+/// Return the closest points between the segments (p0, p1) and (q0, q1).
+///
+/// Degenerate (zero-length) segments are treated as points. For
+/// parallel segments, one of the closest pairs is returned.
+pub fn closest_points_segments<F: Scalar>(
+    p0: &Point3<F>,
+    p1: &Point3<F>,
+    q0: &Point3<F>,
+    q1: &Point3<F>,
+) -> ClosestPoints<F> {
+    let eps = F::default_epsilon();
+    let d1 = p1 - p0;
+    let d2 = q1 - q0;
+    let r = p0 - q0;
+    let a = d1.norm_squared();
+    let e = d2.norm_squared();
+    let f = d2.dot(&r);
+
+    let (s, t) = if a <= eps && e <= eps {
+        (F::zero(), F::zero())
+    } else if a <= eps {
+        (F::zero(), clamp01(f / e))
+    } else {
+        let c = d1.dot(&r);
+        if e <= eps {
+            (clamp01(-c / a), F::zero())
+        } else {
+            let b = d1.dot(&d2);
+            let denom = a * e - b * b;
+            // non-parallel segments: closest point on the infinite
+            // lines, clamped to the first segment.
+            let s = if denom > eps * a * e {
+                clamp01((b * f - c * e) / denom)
+            } else {
+                F::zero()
+            };
+            let t = (b * s + f) / e;
+            if t < F::zero() {
+                (clamp01(-c / a), F::zero())
+            } else if t > F::one() {
+                (clamp01((b - c) / a), F::one())
+            } else {
+                (s, t)
+            }
+        }
+    };
+
+    ClosestPoints {
+        s,
+        t,
+        p: p0 + d1 * s,
+        q: q0 + d2 * t,
+    }
+}
+
+/// Return the distance t >= 0 along the ray (`origin`, `dir`) at
+/// which it crosses the plane, if it does.
+///
+/// The plane is (n, d), with points p on the plane satisfying n · p +
+/// d = 0, as in `Frustum`.
+pub fn ray_plane_intersect<F: Scalar>(
+    origin: &Point3<F>,
+    dir: &Vector3<F>,
+    plane: &Vector4<F>,
+) -> Option<F> {
+    let n = plane.xyz();
+    let denom = n.dot(dir);
+    if denom.abs() <= F::default_epsilon() * n.norm() * dir.norm() {
+        return None;
+    }
+    let t = -(n.dot(&origin.coords) + plane.w) / denom;
+    if t >= F::zero() {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+/// Intersect a ray with the triangle (a, b, c), using Möller–Trumbore.
+///
+/// Both sides of the triangle are hit. Rays in the plane of the
+/// triangle never hit.
+pub fn ray_triangle_intersect<F: Scalar>(
+    origin: &Point3<F>,
+    dir: &Vector3<F>,
+    a: &Point3<F>,
+    b: &Point3<F>,
+    c: &Point3<F>,
+) -> Option<TriangleHit<F>> {
+    let zero = F::zero();
+    let e1 = b - a;
+    let e2 = c - a;
+    let pvec = dir.cross(&e2);
+    let det = e1.dot(&pvec);
+    if det.abs() <= F::default_epsilon() * e1.norm() * e2.norm() * dir.norm() {
+        return None;
+    }
+
+    let inv_det = F::one() / det;
+    let tvec = origin - a;
+    let u = tvec.dot(&pvec) * inv_det;
+    if u < zero || u > F::one() {
+        return None;
+    }
+    let qvec = tvec.cross(&e1);
+    let v = dir.dot(&qvec) * inv_det;
+    if v < zero || u + v > F::one() {
+        return None;
+    }
+    let t = e2.dot(&qvec) * inv_det;
+    if t < zero {
+        return None;
+    }
+    Some(TriangleHit { t, u, v })
+}
+
+/// Intersect a ray with a box using the slab test, returning the
+/// range of distances (t_enter, t_exit) along the ray within the box.
+///
+/// t_enter is clamped to 0 when the origin lies inside the box.
+pub fn ray_aabb_intersect<F: Scalar>(
+    origin: &Point3<F>,
+    dir: &Vector3<F>,
+    b: &Aabb<F>,
+) -> Option<(F, F)> {
+    let mut t0 = F::zero();
+    let mut t1 = F::max_value();
+    for i in 0..3 {
+        if dir[i] == F::zero() {
+            if origin[i] < b.p[0][i] || origin[i] > b.p[1][i] {
+                return None;
+            }
+            continue;
+        }
+        let inv = F::one() / dir[i];
+        let ta = (b.p[0][i] - origin[i]) * inv;
+        let tb = (b.p[1][i] - origin[i]) * inv;
+        let (ta, tb) = if ta > tb { (tb, ta) } else { (ta, tb) };
+        t0 = t0.max(ta);
+        t1 = t1.min(tb);
+        if t0 > t1 {
+            return None;
+        }
+    }
+    Some((t0, t1))
+}
@@ -4,34 +4,6 @@ use nalgebra as na;
 use nalgebra_glm::TMat2;
 use num_traits::Zero;
 
-pub fn refract_dir(
-    incident: na::Vector2<f32>,
-    normal: na::Vector2<f32>,
-    n1: f32,
-    n2: f32,
-) -> Option<na::Vector2<f32>> {
-    let cos_ti = normal.dot(&incident) / (normal.norm() * incident.norm());
-    let n = n1 / n2;
-    let sin_tr = n * (1.0 - cos_ti * cos_ti).sqrt();
-    // sufficiently close to the critical angle.
-    if (sin_tr - 1.0).abs() < 1e-5 {
-        return None;
-    }
-
-    // refract or reflect, depending on the angle
-    let new_dir = if sin_tr > 1.0 {
-        // total internal reflection
-        incident - 2.0 * incident.dot(&normal) * normal
-    } else {
-        // refract at the new angle.
-        let c1 = cos_ti;
-        let c2 = (1.0 - n * n * (1.0 - c1 * c1)).sqrt();
-        n * incident + (n * c1 - c2) * normal
-    };
-
-    Some(new_dir)
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RayInt<T: Scalar> {
     Colinear,
@@ -94,7 +66,7 @@ pub fn orient_2d<F: Scalar>(p: Point2<F>, a: Point2<F>, b: Point2<F>) -> PointTe
     let thresh = 1.0e-8 * (l01 * l12).into();
     if f_sa > thresh {
         PointTest::Inside
-    } else if -f_sa < -thresh {
+    } else if f_sa < -thresh {
         PointTest::Outside
     } else {
         PointTest::On
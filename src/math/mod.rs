@@ -1,11 +1,26 @@
 pub mod clipping;
+pub mod intersect_3d;
 pub mod line_intersect;
+pub mod polygon;
+pub mod refraction;
 pub mod root_finder;
+pub mod transform;
 pub mod types;
 
 pub use clipping::{clip_line, clip_polyline, ClipResult};
-pub use line_intersect::{
-    implicit_ray_intersect_2d, line_intersect_2d, orient_2d, refract_dir, PointTest,
+pub use intersect_3d::{
+    closest_point_on_segment, closest_point_on_triangle, closest_points_segments,
+    point_segment_distance, ray_aabb_intersect, ray_plane_intersect, ray_triangle_intersect,
+    ClosestPoints, TriangleHit,
 };
+pub use line_intersect::{implicit_ray_intersect_2d, line_intersect_2d, orient_2d, PointTest};
+pub use polygon::{
+    centroid, is_convex, is_simple, orientation, perimeter, point_in_polygon_even_odd,
+    point_in_polygon_winding, self_intersections, signed_area, winding_number, Orientation,
+};
+pub use refraction::{fresnel_reflectance, reflect_dir, refract_dir, refract_dir_3d, Refraction};
 pub use root_finder::find_root;
-pub use types::{Rect, Scalar, ToArray};
+pub use transform::Transform2;
+#[cfg(feature = "lapack")]
+pub use types::LapackScalar;
+pub use types::{Aabb, Obb, Rect, Scalar, ToArray};
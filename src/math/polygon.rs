@@ -0,0 +1,223 @@
+//! Measures and point queries on simple 2D polygons.
+//!
+//! Polygons are given as a slice of vertices, with an implicit edge
+//! from the last vertex back to the first.
+use crate::math::line_intersect::RayInt;
+use crate::math::{line_intersect_2d, orient_2d, PointTest, Scalar};
+use na::Point2;
+use nalgebra as na;
+
+/// Winding direction of a polygon.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Orientation {
+    CounterClockwise,
+    Clockwise,
+    /// The polygon has (nearly) zero area.
+    Degenerate,
+}
+
+/// Iterate over the edges of a polygon, including the closing edge.
+fn edges<F: Scalar>(poly: &[Point2<F>]) -> impl Iterator<Item = (Point2<F>, Point2<F>)> + '_ {
+    let n = poly.len();
+    (0..n).map(move |i| (poly[i], poly[(i + 1) % n]))
+}
+
+/// 2D cross product of (a - o) and (b - o).
+fn cross<F: Scalar>(o: &Point2<F>, a: &Point2<F>, b: &Point2<F>) -> F {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+/// Return the signed area of the polygon, positive when the
+/// vertices are in counter-clockwise order.
+pub fn signed_area<F: Scalar>(poly: &[Point2<F>]) -> F {
+    let twice: F = edges(poly).fold(F::zero(), |acc, (a, b)| acc + (a.x * b.y - b.x * a.y));
+    twice * na::convert(0.5)
+}
+
+/// Return the total length of the polygon boundary.
+pub fn perimeter<F: Scalar>(poly: &[Point2<F>]) -> F {
+    edges(poly).fold(F::zero(), |acc, (a, b)| acc + (b - a).norm())
+}
+
+/// Return the area centroid of the polygon, or `None` if the polygon
+/// has zero area.
+pub fn centroid<F: Scalar>(poly: &[Point2<F>]) -> Option<Point2<F>> {
+    let area = signed_area(poly);
+    if area == F::zero() {
+        return None;
+    }
+    // offset by the first vertex to reduce cancellation error
+    let o = *poly.first()?;
+    let mut c = na::Vector2::zeros();
+    for (a, b) in edges(poly) {
+        let (a, b) = (a - o, b - o);
+        let w = a.x * b.y - b.x * a.y;
+        c += (a + b) * w;
+    }
+    let six: F = na::convert(6.0);
+    Some(o + c / (six * area))
+}
+
+/// Return the winding direction of the polygon.
+pub fn orientation<F: Scalar>(poly: &[Point2<F>]) -> Orientation {
+    let area = signed_area(poly);
+    let scale = perimeter(poly);
+    let thresh: F = scale * scale * na::convert(1e-12);
+    if area > thresh {
+        Orientation::CounterClockwise
+    } else if area < -thresh {
+        Orientation::Clockwise
+    } else {
+        Orientation::Degenerate
+    }
+}
+
+/// Return true iff `p` lies on the segment (a, b).
+fn on_segment<F: Scalar>(p: &Point2<F>, a: &Point2<F>, b: &Point2<F>) -> bool {
+    if *p == *a || *p == *b {
+        return true;
+    }
+    orient_2d(*p, *a, *b) == PointTest::On
+        && p.x >= a.x.min(b.x)
+        && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y)
+        && p.y <= a.y.max(b.y)
+}
+
+/// Return true iff `p` lies on the boundary of the polygon.
+fn on_boundary<F: Scalar>(p: &Point2<F>, poly: &[Point2<F>]) -> bool {
+    edges(poly).any(|(a, b)| on_segment(p, &a, &b))
+}
+
+/// Return the winding number of the polygon around `p`.
+///
+/// Counter-clockwise loops contribute positively. Points on the
+/// boundary may be counted either way.
+pub fn winding_number<F: Scalar>(p: &Point2<F>, poly: &[Point2<F>]) -> i32 {
+    let mut wn = 0;
+    for (a, b) in edges(poly) {
+        if a.y <= p.y {
+            if b.y > p.y && cross(&a, &b, p) > F::zero() {
+                wn += 1;
+            }
+        } else if b.y <= p.y && cross(&a, &b, p) < F::zero() {
+            wn -= 1;
+        }
+    }
+    wn
+}
+
+/// Test a point against the polygon using the non-zero winding rule.
+pub fn point_in_polygon_winding<F: Scalar>(p: &Point2<F>, poly: &[Point2<F>]) -> PointTest {
+    if on_boundary(p, poly) {
+        PointTest::On
+    } else if winding_number(p, poly) != 0 {
+        PointTest::Inside
+    } else {
+        PointTest::Outside
+    }
+}
+
+/// Test a point against the polygon using the even-odd rule.
+pub fn point_in_polygon_even_odd<F: Scalar>(p: &Point2<F>, poly: &[Point2<F>]) -> PointTest {
+    if on_boundary(p, poly) {
+        return PointTest::On;
+    }
+    let mut inside = false;
+    for (a, b) in edges(poly) {
+        if (a.y > p.y) != (b.y > p.y) {
+            let x = a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if p.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        PointTest::Inside
+    } else {
+        PointTest::Outside
+    }
+}
+
+/// Return true iff the polygon is convex, in either orientation.
+///
+/// Collinear vertices are allowed, but the polygon must turn in only
+/// one direction and wind around exactly once.
+pub fn is_convex<F: Scalar>(poly: &[Point2<F>]) -> bool {
+    let n = poly.len();
+    if n < 3 {
+        return false;
+    }
+    let mut sign = F::zero();
+    let mut angle_sum = F::zero();
+    for i in 0..n {
+        let (a, b, c) = (poly[i], poly[(i + 1) % n], poly[(i + 2) % n]);
+        let z = cross(&a, &b, &c);
+        if z != F::zero() {
+            if sign * z < F::zero() {
+                return false;
+            }
+            sign = z;
+        }
+        let (u, v) = (b - a, c - b);
+        angle_sum += (u.x * v.y - u.y * v.x).atan2(u.dot(&v));
+    }
+    // reject star-shaped polygons that turn around more than once
+    sign != F::zero() && (angle_sum.abs() - F::two_pi()).abs() < na::convert(1e-3)
+}
+
+/// Return true iff the colinear segments (a0, a1) and (b0, b1) overlap
+/// by more than a point.
+fn colinear_overlap<F: Scalar>(
+    a0: &Point2<F>,
+    a1: &Point2<F>,
+    b0: &Point2<F>,
+    b1: &Point2<F>,
+) -> bool {
+    let d = a1 - a0;
+    let l2 = d.norm_squared();
+    if l2 == F::zero() {
+        return false;
+    }
+    let t0 = (b0 - a0).dot(&d) / l2;
+    let t1 = (b1 - a0).dot(&d) / l2;
+    let (lo, hi) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+    hi.min(F::one()) - lo.max(F::zero()) > na::convert(1e-8)
+}
+
+/// Return the pairs of edge indices (i, j), i < j, whose edges
+/// intersect, where edge i runs from vertex i to vertex i + 1.
+///
+/// Adjacent edges meeting at their shared vertex are not reported.
+pub fn self_intersections<F: Scalar>(poly: &[Point2<F>]) -> Vec<(usize, usize)> {
+    let n = poly.len();
+    let mut isects = vec![];
+    for i in 0..n {
+        let (a0, a1) = (poly[i], poly[(i + 1) % n]);
+        for j in (i + 1)..n {
+            let (b0, b1) = (poly[j], poly[(j + 1) % n]);
+            let adjacent = j == i + 1 || (i == 0 && j == n - 1);
+            let hit = match line_intersect_2d(a0, a1, b0, b1) {
+                RayInt::Intersection(_, _) => !adjacent,
+                RayInt::Colinear => colinear_overlap(&a0, &a1, &b0, &b1),
+                RayInt::Parallel => {
+                    // touching at a non-shared vertex
+                    !adjacent
+                        && (on_segment(&b0, &a0, &a1)
+                            || on_segment(&b1, &a0, &a1)
+                            || on_segment(&a0, &b0, &b1)
+                            || on_segment(&a1, &b0, &b1))
+                }
+            };
+            if hit {
+                isects.push((i, j));
+            }
+        }
+    }
+    isects
+}
+
+/// Return true iff no two edges of the polygon intersect.
+pub fn is_simple<F: Scalar>(poly: &[Point2<F>]) -> bool {
+    poly.len() >= 3 && self_intersections(poly).is_empty()
+}
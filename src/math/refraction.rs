@@ -0,0 +1,127 @@
+//! Refraction and reflection of rays at the interface between two media.
+use crate::math::Scalar;
+use na::allocator::Allocator;
+use na::{DefaultAllocator, DimName, Vector2, Vector3, VectorN};
+use nalgebra as na;
+
+/// Result of a ray crossing an interface between two media.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Refraction<V, F> {
+    /// The ray passes through the interface along `dir`. A fraction
+    /// `reflectance` of the (unpolarized) intensity is reflected.
+    Refracted { dir: V, reflectance: F },
+
+    /// The ray is past the critical angle and is entirely reflected
+    /// along the direction.
+    TotallyReflected(V),
+
+    /// The ray is within tolerance of the critical angle. The
+    /// transmitted ray would travel along the surface `tangent`, but
+    /// all of its intensity is reflected.
+    Grazing { reflected: V, tangent: V },
+}
+
+impl<V: Copy, F: Scalar> Refraction<V, F> {
+    /// Return the direction along which the bulk of the ray
+    /// continues: the refracted direction if there is one, and the
+    /// reflected direction otherwise.
+    pub fn dir(&self) -> V {
+        match self {
+            Self::Refracted { dir, .. } | Self::TotallyReflected(dir) => *dir,
+            Self::Grazing { reflected, .. } => *reflected,
+        }
+    }
+
+    /// Return the fraction of intensity that is reflected.
+    pub fn reflectance(&self) -> F {
+        match self {
+            Self::Refracted { reflectance, .. } => *reflectance,
+            _ => F::one(),
+        }
+    }
+}
+
+/// Return the unpolarized Fresnel reflectance for a ray crossing from
+/// index `n1` to `n2`, given the cosines of the incident and
+/// transmitted angles.
+pub fn fresnel_reflectance<F: Scalar>(cos_i: F, cos_t: F, n1: F, n2: F) -> F {
+    let rs = (n1 * cos_i - n2 * cos_t) / (n1 * cos_i + n2 * cos_t);
+    let rp = (n1 * cos_t - n2 * cos_i) / (n1 * cos_t + n2 * cos_i);
+    (rs * rs + rp * rp) * na::convert(0.5)
+}
+
+/// Reflect the direction `dir` about a surface with normal `normal`.
+pub fn reflect_dir<F: Scalar, D: DimName>(
+    dir: &VectorN<F, D>,
+    normal: &VectorN<F, D>,
+) -> VectorN<F, D>
+where
+    DefaultAllocator: Allocator<F, D>,
+{
+    let n = normal.normalize();
+    let d = dir.dot(&n);
+    dir - n * (d + d)
+}
+
+fn refract<F: Scalar, D: DimName>(
+    incident: &VectorN<F, D>,
+    normal: &VectorN<F, D>,
+    n1: F,
+    n2: F,
+) -> Refraction<VectorN<F, D>, F>
+where
+    DefaultAllocator: Allocator<F, D>,
+{
+    let one = F::one();
+    let incident = incident.normalize();
+    // orient the normal against the incident direction
+    let normal = if normal.dot(&incident) > F::zero() {
+        -normal.normalize()
+    } else {
+        normal.normalize()
+    };
+    let cos_ti = -normal.dot(&incident);
+    let n = n1 / n2;
+    let sin_tr = n * (one - cos_ti * cos_ti).max(F::zero()).sqrt();
+    let reflected = &incident + &normal * (cos_ti + cos_ti);
+
+    // sufficiently close to the critical angle.
+    if (sin_tr - one).abs() < F::default_epsilon().sqrt() {
+        let tangent = (&incident + &normal * cos_ti).normalize();
+        return Refraction::Grazing { reflected, tangent };
+    }
+    if sin_tr > one {
+        return Refraction::TotallyReflected(reflected);
+    }
+
+    let cos_tr = (one - sin_tr * sin_tr).sqrt();
+    Refraction::Refracted {
+        dir: incident * n + normal * (n * cos_ti - cos_tr),
+        reflectance: fresnel_reflectance(cos_ti, cos_tr, n1, n2),
+    }
+}
+
+/// Return the result of a ray travelling along `incident` crossing an
+/// interface with surface normal `normal`, going from a medium with
+/// refractive index `n1` into one with index `n2`.
+///
+/// The normal may face either side of the interface. Returned
+/// directions are normalized.
+pub fn refract_dir<F: Scalar>(
+    incident: Vector2<F>,
+    normal: Vector2<F>,
+    n1: F,
+    n2: F,
+) -> Refraction<Vector2<F>, F> {
+    refract(&incident, &normal, n1, n2)
+}
+
+/// 3D counterpart of `refract_dir`.
+pub fn refract_dir_3d<F: Scalar>(
+    incident: Vector3<F>,
+    normal: Vector3<F>,
+    n1: F,
+    n2: F,
+) -> Refraction<Vector3<F>, F> {
+    refract(&incident, &normal, n1, n2)
+}
@@ -0,0 +1,214 @@
+//! 2D affine transforms.
+use crate::math::{Rect, Scalar};
+use na::{Matrix3, Point2, Vector2};
+use nalgebra as na;
+use std::fmt::Display;
+use std::ops::Mul;
+
+/// An affine transform of the plane, stored as a 3x3 matrix acting on
+/// homogeneous column vectors.
+///
+/// `a * b` applies `b` first, then `a`. `a.then(&b)` applies `a`
+/// first, which reads better when building a transform step by step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform2<F: Scalar> {
+    m: Matrix3<F>,
+}
+
+impl<F: Scalar> Transform2<F> {
+    pub fn identity() -> Transform2<F> {
+        Transform2 {
+            m: Matrix3::identity(),
+        }
+    }
+
+    /// Create a transform from an affine matrix. The last row should
+    /// be (0, 0, 1).
+    pub fn from_matrix(m: Matrix3<F>) -> Transform2<F> {
+        Transform2 { m }
+    }
+
+    /// Create a transform from SVG-style coefficients, mapping (x, y)
+    /// to (a x + c y + e, b x + d y + f).
+    pub fn from_coefficients(a: F, b: F, c: F, d: F, e: F, f: F) -> Transform2<F> {
+        let (zero, one) = (F::zero(), F::one());
+        Transform2 {
+            m: Matrix3::new(a, c, e, b, d, f, zero, zero, one),
+        }
+    }
+
+    pub fn translation(v: &Vector2<F>) -> Transform2<F> {
+        let (zero, one) = (F::zero(), F::one());
+        Self::from_coefficients(one, zero, zero, one, v.x, v.y)
+    }
+
+    /// Counter-clockwise rotation about the origin, in radians.
+    pub fn rotation(angle: F) -> Transform2<F> {
+        let (s, c) = angle.sin_cos();
+        Self::from_coefficients(c, s, -s, c, F::zero(), F::zero())
+    }
+
+    /// Counter-clockwise rotation about `center`, in radians.
+    pub fn rotation_about(angle: F, center: &Point2<F>) -> Transform2<F> {
+        Self::translation(&-center.coords)
+            .then(&Self::rotation(angle))
+            .then(&Self::translation(&center.coords))
+    }
+
+    pub fn scaling(sx: F, sy: F) -> Transform2<F> {
+        let zero = F::zero();
+        Self::from_coefficients(sx, zero, zero, sy, zero, zero)
+    }
+
+    pub fn uniform_scaling(s: F) -> Transform2<F> {
+        Self::scaling(s, s)
+    }
+
+    /// Shear mapping (x, y) to (x + shx y, y + shy x).
+    pub fn shear(shx: F, shy: F) -> Transform2<F> {
+        let (zero, one) = (F::zero(), F::one());
+        Self::from_coefficients(one, shy, shx, one, zero, zero)
+    }
+
+    /// Return a transform applying `self`, then `other`.
+    pub fn then(&self, other: &Transform2<F>) -> Transform2<F> {
+        Transform2 {
+            m: other.m * self.m,
+        }
+    }
+
+    /// Return the inverse transform, or `None` if the transform is
+    /// singular.
+    pub fn inverse(&self) -> Option<Transform2<F>> {
+        self.m.try_inverse().map(|m| Transform2 { m })
+    }
+
+    pub fn matrix(&self) -> &Matrix3<F> {
+        &self.m
+    }
+
+    /// Return the SVG-style coefficients (a, b, c, d, e, f).
+    pub fn coefficients(&self) -> [F; 6] {
+        let m = &self.m;
+        [
+            m[(0, 0)],
+            m[(1, 0)],
+            m[(0, 1)],
+            m[(1, 1)],
+            m[(0, 2)],
+            m[(1, 2)],
+        ]
+    }
+
+    pub fn apply_point(&self, p: &Point2<F>) -> Point2<F> {
+        self.m.transform_point(p)
+    }
+
+    /// Apply the linear part of the transform to a vector, ignoring
+    /// translation.
+    pub fn apply_vector(&self, v: &Vector2<F>) -> Vector2<F> {
+        self.m.transform_vector(v)
+    }
+
+    /// Apply the transform to each point of a polyline or polygon.
+    pub fn apply_points(&self, points: &[Point2<F>]) -> Vec<Point2<F>> {
+        points.iter().map(|p| self.apply_point(p)).collect()
+    }
+
+    /// Return the bounding rectangle of the transformed rectangle.
+    pub fn apply_rect(&self, r: &Rect<F>) -> Rect<F> {
+        Rect::bounding(&self.apply_points(&r.corners())).unwrap()
+    }
+
+    /// Return the transform mapping `src` into `dst`, scaled
+    /// uniformly to fit and centered.
+    ///
+    /// Set `flip_y` to flip the y-axis, e.g. to map y-up drawing
+    /// coordinates onto a y-down page.
+    pub fn fit_rect(src: &Rect<F>, dst: &Rect<F>, flip_y: bool) -> Transform2<F> {
+        let (sd, dd) = (src.dim(), dst.dim());
+        let sx = if sd.x > F::zero() {
+            dd.x / sd.x
+        } else {
+            F::max_value()
+        };
+        let sy = if sd.y > F::zero() {
+            dd.y / sd.y
+        } else {
+            F::max_value()
+        };
+        let mut s = sx.min(sy);
+        if s == F::max_value() {
+            s = F::one();
+        }
+        Self::fit_with_scale(src, dst, s, s, flip_y)
+    }
+
+    /// Return the transform mapping `src` onto `dst`, scaling each
+    /// axis independently.
+    ///
+    /// An axis along which `src` has zero size is not scaled, and is
+    /// centered on `dst` instead.
+    pub fn stretch_rect(src: &Rect<F>, dst: &Rect<F>, flip_y: bool) -> Transform2<F> {
+        let (sd, dd) = (src.dim(), dst.dim());
+        let scale = |s: F, d: F| if s > F::zero() { d / s } else { F::one() };
+        Self::fit_with_scale(src, dst, scale(sd.x, dd.x), scale(sd.y, dd.y), flip_y)
+    }
+
+    /// Return the transform mapping the bounding box of the points
+    /// into `dst`, preserving aspect, with a margin on each side.
+    pub fn fit_points(
+        points: &[Point2<F>],
+        dst: &Rect<F>,
+        margin: F,
+        flip_y: bool,
+    ) -> Transform2<F> {
+        let m = Vector2::new(margin, margin);
+        let inner = Rect::from_points(&(dst.p[0] + m), &(dst.p[1] - m));
+        match Rect::bounding(points) {
+            Some(src) => Self::fit_rect(&src, &inner, flip_y),
+            None => Self::identity(),
+        }
+    }
+
+    fn fit_with_scale(src: &Rect<F>, dst: &Rect<F>, sx: F, sy: F, flip_y: bool) -> Transform2<F> {
+        let sy = if flip_y { -sy } else { sy };
+        Self::translation(&-src.center().coords)
+            .then(&Self::scaling(sx, sy))
+            .then(&Self::translation(&dst.center().coords))
+    }
+}
+
+impl<F: Scalar + Display> Transform2<F> {
+    /// Return the value of an SVG `transform` attribute for the
+    /// transform.
+    pub fn to_svg_attribute(&self) -> String {
+        let c = self.coefficients();
+        format!(
+            "matrix({} {} {} {} {} {})",
+            c[0], c[1], c[2], c[3], c[4], c[5]
+        )
+    }
+}
+
+impl<F: Scalar> Default for Transform2<F> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<F: Scalar> Mul for Transform2<F> {
+    type Output = Transform2<F>;
+
+    fn mul(self, rhs: Transform2<F>) -> Transform2<F> {
+        Transform2 { m: self.m * rhs.m }
+    }
+}
+
+impl<F: Scalar> Mul<Point2<F>> for Transform2<F> {
+    type Output = Point2<F>;
+
+    fn mul(self, rhs: Point2<F>) -> Point2<F> {
+        self.apply_point(&rhs)
+    }
+}
@@ -1,10 +1,18 @@
-use na::{center, Point2, Vector2};
+use na::{center, Point2, Point3, Vector2, Vector3};
 use nalgebra as na;
+#[cfg(feature = "lapack")]
 use ndarray_linalg::{Lapack, Scalar as NDScalar};
 use std::fmt::Debug;
 
-pub trait Scalar: na::RealField + Into<f64> + NDScalar + Lapack + Debug + Copy + 'static {}
-impl<T> Scalar for T where T: na::RealField + Into<f64> + NDScalar + Lapack + Debug + Copy + 'static {}
+/// Real scalar type used throughout the geometry code.
+pub trait Scalar: na::RealField + Into<f64> + Debug + Copy + 'static {}
+impl<T> Scalar for T where T: na::RealField + Into<f64> + Debug + Copy + 'static {}
+
+/// Scalar type usable with the LAPACK-backed solvers.
+#[cfg(feature = "lapack")]
+pub trait LapackScalar: Scalar + NDScalar<Real = Self> + Lapack {}
+#[cfg(feature = "lapack")]
+impl<T> LapackScalar for T where T: Scalar + NDScalar<Real = T> + Lapack {}
 
 /// Simple 2D rectangle, for common bounding-box style operations.
 #[derive(Clone, Copy, Debug)]
@@ -52,6 +60,145 @@ impl<F: Scalar> Rect<F> {
     pub fn center(&self) -> Point2<F> {
         center(&self.p[0], &self.p[1])
     }
+
+    /// Return the smallest rectangle containing all of the points, or
+    /// `None` if there are no points.
+    pub fn bounding(points: &[Point2<F>]) -> Option<Rect<F>> {
+        let first = points.first()?;
+        let mut r = Rect::from_points(first, first);
+        for p in &points[1..] {
+            r.extend(p);
+        }
+        Some(r)
+    }
+
+    /// Grow the rectangle to contain the point.
+    pub fn extend(&mut self, p: &Point2<F>) {
+        self.p[0] = self.p[0].inf(p);
+        self.p[1] = self.p[1].sup(p);
+    }
+}
+
+/// Axis-aligned 3D box, the 3D counterpart of `Rect`.
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb<F: Scalar> {
+    pub p: [Point3<F>; 2],
+}
+
+impl<F: Scalar> Aabb<F> {
+    /// Create a box from the minimum and maximum corner points.
+    pub fn from_points(a: &Point3<F>, b: &Point3<F>) -> Aabb<F> {
+        Aabb { p: [*a, *b] }
+    }
+
+    /// Create a box from its center and half-widths along each axis.
+    pub fn from_center_half_widths(c: &Point3<F>, h: &Vector3<F>) -> Aabb<F> {
+        Aabb { p: [c - h, c + h] }
+    }
+
+    /// Return the smallest box containing all of the points, or
+    /// `None` if there are no points.
+    pub fn bounding(points: &[Point3<F>]) -> Option<Aabb<F>> {
+        let first = points.first()?;
+        let mut b = Aabb::from_points(first, first);
+        for p in &points[1..] {
+            b.extend(p);
+        }
+        Some(b)
+    }
+
+    /// Grow the box to contain the point.
+    pub fn extend(&mut self, p: &Point3<F>) {
+        self.p[0] = self.p[0].inf(p);
+        self.p[1] = self.p[1].sup(p);
+    }
+
+    /// Return the smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb<F>) -> Aabb<F> {
+        Aabb {
+            p: [self.p[0].inf(&other.p[0]), self.p[1].sup(&other.p[1])],
+        }
+    }
+
+    /// Return the dimensions of the box.
+    pub fn dim(&self) -> Vector3<F> {
+        self.p[1] - self.p[0]
+    }
+
+    pub fn center(&self) -> Point3<F> {
+        center(&self.p[0], &self.p[1])
+    }
+
+    /// Return the corners of the box, with x varying fastest, then y,
+    /// then z.
+    pub fn corners(&self) -> [Point3<F>; 8] {
+        let (a, b) = (self.p[0], self.p[1]);
+        [
+            Point3::new(a.x, a.y, a.z),
+            Point3::new(b.x, a.y, a.z),
+            Point3::new(a.x, b.y, a.z),
+            Point3::new(b.x, b.y, a.z),
+            Point3::new(a.x, a.y, b.z),
+            Point3::new(b.x, a.y, b.z),
+            Point3::new(a.x, b.y, b.z),
+            Point3::new(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Return true iff the point lies within or on the box.
+    pub fn contains(&self, p: &Point3<F>) -> bool {
+        (0..3).all(|i| self.p[0][i] <= p[i] && p[i] <= self.p[1][i])
+    }
+}
+
+/// Oriented 3D box.
+#[derive(Clone, Copy, Debug)]
+pub struct Obb<F: Scalar> {
+    pub center: Point3<F>,
+
+    /// Unit axes of the box.
+    pub axes: [Vector3<F>; 3],
+
+    /// Half-widths of the box along each axis.
+    pub half_widths: Vector3<F>,
+}
+
+impl<F: Scalar> Obb<F> {
+    pub fn new(center: Point3<F>, axes: [Vector3<F>; 3], half_widths: Vector3<F>) -> Obb<F> {
+        Obb {
+            center,
+            axes,
+            half_widths,
+        }
+    }
+
+    /// Create the box resulting from applying an isometry to an
+    /// axis-aligned box.
+    pub fn from_aabb(b: &Aabb<F>, iso: &na::Isometry3<F>) -> Obb<F> {
+        let r = iso.rotation;
+        Obb {
+            center: iso * b.center(),
+            axes: [
+                r * Vector3::x_axis().into_inner(),
+                r * Vector3::y_axis().into_inner(),
+                r * Vector3::z_axis().into_inner(),
+            ],
+            half_widths: b.dim() * na::convert::<f64, F>(0.5),
+        }
+    }
+
+    /// Return the corners of the box, in the same order as
+    /// `Aabb::corners` with respect to the box axes.
+    pub fn corners(&self) -> [Point3<F>; 8] {
+        let mut corners = [self.center; 8];
+        for (i, c) in corners.iter_mut().enumerate() {
+            for k in 0..3 {
+                let h = self.axes[k] * self.half_widths[k];
+                *c += if i & (1 << k) == 0 { -h } else { h };
+            }
+        }
+        corners
+    }
 }
 
 pub trait ToArray<T: Copy> {
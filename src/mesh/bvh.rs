@@ -0,0 +1,346 @@
+//! Bounding volume hierarchy for ray and distance queries on
+//! triangle meshes.
+use super::Mesh;
+use crate::math::{closest_point_on_triangle, ray_aabb_intersect, ray_triangle_intersect};
+use crate::math::{Aabb, Scalar};
+use na::{Point3, Vector3};
+use nalgebra as na;
+
+/// The most triangles in a leaf.
+const LEAF_SIZE: usize = 4;
+
+/// A ray hit on a triangle of a `Bvh`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeshHit<F: Scalar> {
+    /// Index of the triangle hit.
+    pub triangle: usize,
+    /// Distance along the ray, in units of the ray direction.
+    pub t: F,
+    /// Barycentric coordinates of the hit with respect to the second
+    /// and third vertices of the triangle.
+    pub u: F,
+    pub v: F,
+}
+
+/// The closest point on a `Bvh` to a query point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NearestPoint<F: Scalar> {
+    pub triangle: usize,
+    pub point: Point3<F>,
+    pub distance: F,
+}
+
+#[derive(Clone, Debug)]
+struct Node<F: Scalar> {
+    bounds: Aabb<F>,
+    /// For leaves, the first entry of `order` in the leaf. Otherwise,
+    /// the index of the second child; the first child follows the
+    /// node.
+    index: usize,
+    /// The number of triangles in a leaf, or 0 for inner nodes.
+    count: usize,
+}
+
+/// A bounding volume hierarchy over a set of triangles.
+///
+/// Triangles are indexed in the order they were given, e.g. the
+/// triangle order of the mesh.
+#[derive(Clone, Debug)]
+pub struct Bvh<F: Scalar> {
+    triangles: Vec<[Point3<F>; 3]>,
+    nodes: Vec<Node<F>>,
+    /// Triangle indices, ordered so that each leaf covers a range.
+    order: Vec<usize>,
+}
+
+fn triangle_bounds<F: Scalar>(t: &[Point3<F>; 3]) -> Aabb<F> {
+    let mut b = Aabb::from_points(&t[0], &t[0]);
+    b.extend(&t[1]);
+    b.extend(&t[2]);
+    b
+}
+
+/// Squared distance from a point to a box, or zero inside it.
+fn box_distance_squared<F: Scalar>(p: &Point3<F>, b: &Aabb<F>) -> F {
+    (0..3)
+        .map(|i| {
+            let d = (b.p[0][i] - p[i]).max(p[i] - b.p[1][i]).max(F::zero());
+            d * d
+        })
+        .fold(F::zero(), |a, b| a + b)
+}
+
+impl<F: Scalar> Bvh<F> {
+    /// Build a hierarchy over the triangles, splitting nodes at the
+    /// median centroid along their longest axis.
+    pub fn from_triangles(triangles: Vec<[Point3<F>; 3]>) -> Bvh<F> {
+        let third: F = na::convert(1.0 / 3.0);
+        let centroids: Vec<Point3<F>> = triangles
+            .iter()
+            .map(|t| Point3::from((t[0].coords + t[1].coords + t[2].coords) * third))
+            .collect();
+        let mut bvh = Bvh {
+            nodes: vec![],
+            order: (0..triangles.len()).collect(),
+            triangles,
+        };
+        if !bvh.triangles.is_empty() {
+            bvh.build(&centroids, 0, bvh.triangles.len());
+        }
+        bvh
+    }
+
+    /// Build a hierarchy over the triangles of a mesh.
+    pub fn new(mesh: &Mesh<F>) -> Bvh<F> {
+        Bvh::from_triangles(
+            (0..mesh.num_triangles())
+                .map(|i| mesh.triangle_positions(i))
+                .collect(),
+        )
+    }
+
+    /// Add the subtree for order[start..end], returning its index.
+    fn build(&mut self, centroids: &[Point3<F>], start: usize, end: usize) -> usize {
+        let items = &mut self.order[start..end];
+        let triangles = &self.triangles;
+        let bounds = items
+            .iter()
+            .map(|&i| triangle_bounds(&triangles[i]))
+            .fold(triangle_bounds(&triangles[items[0]]), |a, b| a.union(&b));
+
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            bounds,
+            index: start,
+            count: end - start,
+        });
+        if end - start <= LEAF_SIZE {
+            return node;
+        }
+
+        let mut cb = Aabb::from_points(&centroids[items[0]], &centroids[items[0]]);
+        for &i in items.iter() {
+            cb.extend(&centroids[i]);
+        }
+        let d = cb.dim();
+        let axis = if d.x >= d.y && d.x >= d.z {
+            0
+        } else if d.y >= d.z {
+            1
+        } else {
+            2
+        };
+        if d[axis] <= F::zero() {
+            // all centroids coincide, so splitting won't help
+            return node;
+        }
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |&a, &b| {
+            centroids[a][axis]
+                .partial_cmp(&centroids[b][axis])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        self.build(centroids, start, start + mid);
+        let second = self.build(centroids, start + mid, end);
+        self.nodes[node].index = second;
+        self.nodes[node].count = 0;
+        node
+    }
+
+    pub fn num_triangles(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Return the corners of triangle `i`.
+    pub fn triangle(&self, i: usize) -> &[Point3<F>; 3] {
+        &self.triangles[i]
+    }
+
+    /// Return the bounds of all of the triangles, or `None` if there
+    /// are none.
+    pub fn bounds(&self) -> Option<Aabb<F>> {
+        self.nodes.first().map(|n| n.bounds)
+    }
+
+    /// Visit the triangles whose leaves the ray reaches between
+    /// `t_min` and the current limit, nearest nodes first.
+    ///
+    /// `visit` gets each triangle and returns the new limit on t, or
+    /// `None` to stop.
+    fn traverse_ray<V>(
+        &self,
+        origin: &Point3<F>,
+        dir: &Vector3<F>,
+        t_min: F,
+        t_max: F,
+        mut visit: V,
+    ) where
+        V: FnMut(usize) -> Option<F>,
+    {
+        let mut limit = t_max;
+        let reach = |node: usize, limit: F| {
+            ray_aabb_intersect(origin, dir, &self.nodes[node].bounds)
+                .filter(|&(t0, t1)| t0 <= limit && t1 >= t_min)
+                .map(|(t0, _)| t0)
+        };
+        if self.nodes.is_empty() || reach(0, limit).is_none() {
+            return;
+        }
+        // nodes to visit, with the distance at which the ray enters
+        // them
+        let mut stack = vec![(0, F::zero())];
+        while let Some((n, enter)) = stack.pop() {
+            if enter > limit {
+                continue;
+            }
+            let node = &self.nodes[n];
+            if node.count > 0 {
+                for &i in &self.order[node.index..node.index + node.count] {
+                    match visit(i) {
+                        Some(l) => limit = limit.min(l),
+                        None => return,
+                    }
+                }
+                continue;
+            }
+            let (a, b) = (n + 1, node.index);
+            match (reach(a, limit), reach(b, limit)) {
+                (Some(ta), Some(tb)) => {
+                    // push the farther child first, to visit the
+                    // nearer one first
+                    if ta <= tb {
+                        stack.extend_from_slice(&[(b, tb), (a, ta)]);
+                    } else {
+                        stack.extend_from_slice(&[(a, ta), (b, tb)]);
+                    }
+                }
+                (Some(ta), None) => stack.push((a, ta)),
+                (None, Some(tb)) => stack.push((b, tb)),
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn hit(&self, i: usize, origin: &Point3<F>, dir: &Vector3<F>) -> Option<MeshHit<F>> {
+        let [a, b, c] = &self.triangles[i];
+        ray_triangle_intersect(origin, dir, a, b, c).map(|h| MeshHit {
+            triangle: i,
+            t: h.t,
+            u: h.u,
+            v: h.v,
+        })
+    }
+
+    /// Return the nearest hit of the ray (`origin`, `dir`) with t in
+    /// [t_min, t_max].
+    ///
+    /// Both sides of the triangles are hit. Use a small t_min to skip
+    /// the surface a ray starts on.
+    pub fn closest_hit(
+        &self,
+        origin: &Point3<F>,
+        dir: &Vector3<F>,
+        t_min: F,
+        t_max: F,
+    ) -> Option<MeshHit<F>> {
+        let mut best: Option<MeshHit<F>> = None;
+        self.traverse_ray(origin, dir, t_min, t_max, |i| {
+            if let Some(h) = self.hit(i, origin, dir) {
+                let limit = best.map_or(t_max, |b| b.t);
+                if h.t >= t_min && h.t < limit {
+                    best = Some(h);
+                }
+            }
+            Some(best.map_or(t_max, |b| b.t))
+        });
+        best
+    }
+
+    /// Return any hit of the ray (`origin`, `dir`) with t in
+    /// [t_min, t_max], which need not be the nearest.
+    pub fn any_hit(
+        &self,
+        origin: &Point3<F>,
+        dir: &Vector3<F>,
+        t_min: F,
+        t_max: F,
+    ) -> Option<MeshHit<F>> {
+        let mut found = None;
+        self.traverse_ray(origin, dir, t_min, t_max, |i| {
+            match self.hit(i, origin, dir) {
+                Some(h) if h.t >= t_min && h.t <= t_max => {
+                    found = Some(h);
+                    None
+                }
+                _ => Some(t_max),
+            }
+        });
+        found
+    }
+
+    /// Return true if any triangle blocks the segment from `a` to `b`.
+    ///
+    /// Hits within `tolerance` of either end are ignored, so that
+    /// points on the mesh itself, e.g. when testing whether a point
+    /// is visible from the eye, are not occluded by their own faces.
+    pub fn is_segment_occluded(&self, a: &Point3<F>, b: &Point3<F>, tolerance: F) -> bool {
+        let dir = b - a;
+        let len = dir.norm();
+        if len <= tolerance * na::convert(2.0) {
+            return false;
+        }
+        let margin = tolerance / len;
+        self.any_hit(a, &dir, margin, F::one() - margin).is_some()
+    }
+
+    /// Return the closest point on any triangle to `p`, or `None` if
+    /// there are no triangles.
+    pub fn nearest_point(&self, p: &Point3<F>) -> Option<NearestPoint<F>> {
+        let mut best: Option<NearestPoint<F>> = None;
+        let mut best_d2 = F::max_value();
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if box_distance_squared(p, &node.bounds) > best_d2 {
+                continue;
+            }
+            if node.count > 0 {
+                for &i in &self.order[node.index..node.index + node.count] {
+                    let [a, b, c] = &self.triangles[i];
+                    let q = closest_point_on_triangle(p, a, b, c);
+                    let d2 = (q - p).norm_squared();
+                    if best.is_none() || d2 < best_d2 {
+                        best_d2 = d2;
+                        best = Some(NearestPoint {
+                            triangle: i,
+                            point: q,
+                            distance: d2.sqrt(),
+                        });
+                    }
+                }
+                continue;
+            }
+            let children = [n + 1, node.index];
+            let da = box_distance_squared(p, &self.nodes[children[0]].bounds);
+            let db = box_distance_squared(p, &self.nodes[children[1]].bounds);
+            if da <= db {
+                stack.extend_from_slice(&[children[1], children[0]]);
+            } else {
+                stack.extend_from_slice(&children);
+            }
+        }
+        best
+    }
+}
+
+impl<F: Scalar> Mesh<F> {
+    /// Build a bounding volume hierarchy over the triangles.
+    pub fn bvh(&self) -> Bvh<F> {
+        Bvh::new(self)
+    }
+}
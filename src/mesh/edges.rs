@@ -0,0 +1,200 @@
+//! Edge adjacency and feature edges of triangle meshes.
+use super::{weld_positions, Mesh};
+use crate::math::Scalar;
+use na::{Point3, Vector3};
+use nalgebra as na;
+use std::collections::HashMap;
+
+/// Classification of a feature edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// An edge with only one adjacent face, or more than two.
+    Boundary,
+
+    /// An edge whose adjacent faces meet at more than the crease
+    /// angle.
+    Crease,
+
+    /// An edge between a face facing the view and one facing away.
+    Silhouette,
+}
+
+/// An edge of a mesh, with the faces that contain it.
+#[derive(Clone, Debug)]
+pub struct MeshEdge {
+    /// Vertex indices, smallest first.
+    pub vertices: [u32; 2],
+    pub faces: Vec<usize>,
+}
+
+/// The unique edges of a triangle mesh, and the normals of its
+/// faces.
+///
+/// Vertices with identical positions are treated as the same vertex,
+/// so seams from duplicated vertices (e.g. for UVs) are not boundaries.
+/// Edges refer to the first vertex at each position.
+#[derive(Clone, Debug)]
+pub struct EdgeAdjacency<F: Scalar> {
+    pub edges: Vec<MeshEdge>,
+    pub face_normals: Vec<Vector3<F>>,
+    /// A point on each face.
+    face_points: Vec<Point3<F>>,
+}
+
+fn line_indices<'a, I: Iterator<Item = &'a MeshEdge>>(edges: I) -> Vec<u32> {
+    edges.flat_map(|e| e.vertices.iter().copied()).collect()
+}
+
+impl<F: Scalar> Mesh<F> {
+    /// Return the unit normal of each triangle, from its winding, or
+    /// zero for degenerate triangles.
+    pub fn face_normals(&self) -> Vec<Vector3<F>> {
+        self.triangles()
+            .map(|t| {
+                let p = [
+                    self.positions[t[0]],
+                    self.positions[t[1]],
+                    self.positions[t[2]],
+                ];
+                (p[1] - p[0])
+                    .cross(&(p[2] - p[0]))
+                    .try_normalize(F::zero())
+                    .unwrap_or_else(Vector3::zeros)
+            })
+            .collect()
+    }
+
+    /// Return the area-weighted average of the normals of the
+    /// triangles around each vertex.
+    pub fn vertex_normals(&self) -> Vec<Vector3<F>> {
+        let mut normals = vec![Vector3::zeros(); self.positions.len()];
+        for t in self.triangles() {
+            let p = [
+                self.positions[t[0]],
+                self.positions[t[1]],
+                self.positions[t[2]],
+            ];
+            // twice the area, in the direction of the normal
+            let n = (p[1] - p[0]).cross(&(p[2] - p[0]));
+            for &i in &t {
+                normals[i] += n;
+            }
+        }
+        for n in normals.iter_mut() {
+            if let Some(u) = n.try_normalize(F::zero()) {
+                *n = u;
+            }
+        }
+        normals
+    }
+
+    pub fn edge_adjacency(&self) -> EdgeAdjacency<F> {
+        EdgeAdjacency::new(self)
+    }
+}
+
+impl<F: Scalar> EdgeAdjacency<F> {
+    pub fn new(mesh: &Mesh<F>) -> EdgeAdjacency<F> {
+        let canonical = weld_positions(&mesh.positions);
+
+        let mut index = HashMap::new();
+        let mut edges: Vec<MeshEdge> = vec![];
+        for (fi, t) in mesh.triangles().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (canonical[t[k]], canonical[t[(k + 1) % 3]]);
+                if a == b {
+                    continue;
+                }
+                let key = [a.min(b), a.max(b)];
+                let ei = *index.entry(key).or_insert_with(|| {
+                    edges.push(MeshEdge {
+                        vertices: key,
+                        faces: vec![],
+                    });
+                    edges.len() - 1
+                });
+                edges[ei].faces.push(fi);
+            }
+        }
+
+        EdgeAdjacency {
+            edges,
+            face_normals: mesh.face_normals(),
+            face_points: (0..mesh.num_triangles())
+                .map(|i| mesh.triangle_positions(i)[0])
+                .collect(),
+        }
+    }
+
+    fn is_front_facing(&self, face: usize, eye: &Point3<F>) -> bool {
+        self.face_normals[face].dot(&(eye - self.face_points[face])) > F::zero()
+    }
+
+    /// Classify an edge, with silhouettes seen from `eye` if given.
+    ///
+    /// Returns `None` for edges that are not feature edges. Edges
+    /// that are both creases and silhouettes are classed as creases.
+    pub fn classify(
+        &self,
+        edge: usize,
+        eye: Option<&Point3<F>>,
+        crease_angle: F,
+    ) -> Option<EdgeKind> {
+        let faces = &self.edges[edge].faces;
+        if faces.len() != 2 {
+            return Some(EdgeKind::Boundary);
+        }
+        let (n0, n1) = (&self.face_normals[faces[0]], &self.face_normals[faces[1]]);
+        let degenerate = n0.norm_squared() == F::zero() || n1.norm_squared() == F::zero();
+        if !degenerate && n0.dot(n1) < crease_angle.cos() {
+            return Some(EdgeKind::Crease);
+        }
+        if let Some(eye) = eye {
+            if self.is_front_facing(faces[0], eye) != self.is_front_facing(faces[1], eye) {
+                return Some(EdgeKind::Silhouette);
+            }
+        }
+        None
+    }
+
+    /// Return all of the edges as line indices.
+    pub fn all_edges(&self) -> Vec<u32> {
+        line_indices(self.edges.iter())
+    }
+
+    /// Return the boundary edges as line indices.
+    pub fn boundary_edges(&self) -> Vec<u32> {
+        line_indices(self.edges.iter().filter(|e| e.faces.len() != 2))
+    }
+
+    /// Return the edges whose faces meet at more than `crease_angle`
+    /// radians, as line indices.
+    pub fn crease_edges(&self, crease_angle: F) -> Vec<u32> {
+        line_indices(
+            (0..self.edges.len())
+                .filter(|&i| self.classify(i, None, crease_angle) == Some(EdgeKind::Crease))
+                .map(|i| &self.edges[i]),
+        )
+    }
+
+    /// Return the silhouette edges seen from `eye`, as line indices.
+    ///
+    /// For an orthographic view, use an eye point far along the
+    /// reverse of the view direction.
+    pub fn silhouette_edges(&self, eye: &Point3<F>) -> Vec<u32> {
+        line_indices(self.edges.iter().filter(|e| {
+            e.faces.len() == 2
+                && self.is_front_facing(e.faces[0], eye) != self.is_front_facing(e.faces[1], eye)
+        }))
+    }
+
+    /// Return the boundary, crease and silhouette edges, each once,
+    /// as line indices.
+    pub fn feature_edges(&self, eye: Option<&Point3<F>>, crease_angle: F) -> Vec<u32> {
+        line_indices(
+            (0..self.edges.len())
+                .filter(|&i| self.classify(i, eye, crease_angle).is_some())
+                .map(|i| &self.edges[i]),
+        )
+    }
+}
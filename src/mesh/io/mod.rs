@@ -0,0 +1,93 @@
+//! Reading and writing meshes in common file formats.
+use super::Mesh;
+use crate::math::Scalar;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+mod obj;
+mod ply;
+mod stl;
+
+pub use obj::{read_obj, write_obj};
+pub use ply::{read_ply, write_ply, PlyFormat};
+pub use stl::{read_stl, write_stl, StlFormat};
+
+#[derive(Debug)]
+pub enum MeshIoError {
+    IoError(std::io::Error),
+
+    /// The file is malformed, with a description of the problem and
+    /// the line number, where one applies.
+    ParseError(String, Option<usize>),
+
+    /// The file uses a feature of the format that is not supported.
+    Unsupported(String),
+}
+
+impl std::fmt::Display for MeshIoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::IoError(e) => write!(f, "IoError: {}", e),
+            Self::ParseError(s, Some(line)) => write!(f, "parse error on line {}: {}", line, s),
+            Self::ParseError(s, None) => write!(f, "parse error: {}", s),
+            Self::Unsupported(s) => write!(f, "unsupported: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for MeshIoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for MeshIoError {
+    fn from(e: std::io::Error) -> MeshIoError {
+        MeshIoError::IoError(e)
+    }
+}
+
+fn parse_error<T>(s: &str, line: Option<usize>) -> Result<T, MeshIoError> {
+    Err(MeshIoError::ParseError(s.to_string(), line))
+}
+
+fn extension(path: &Path) -> Result<String, MeshIoError> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .ok_or_else(|| MeshIoError::Unsupported(format!("no extension on {:?}", path)))
+}
+
+/// Read a mesh from an .obj, .ply or .stl file, chosen by the file
+/// extension.
+pub fn read_mesh<F: Scalar, P: AsRef<Path>>(path: P) -> Result<Mesh<F>, MeshIoError> {
+    let path = path.as_ref();
+    let ext = extension(path)?;
+    let mut r = BufReader::new(File::open(path)?);
+    match ext.as_str() {
+        "obj" => read_obj(&mut r),
+        "ply" => read_ply(&mut r),
+        "stl" => read_stl(&mut r),
+        _ => Err(MeshIoError::Unsupported(format!("extension {}", ext))),
+    }
+}
+
+/// Write a mesh to an .obj, .ply or .stl file, chosen by the file
+/// extension. PLY and STL files are written in binary.
+pub fn write_mesh<F: Scalar, P: AsRef<Path>>(path: P, mesh: &Mesh<F>) -> Result<(), MeshIoError> {
+    let path = path.as_ref();
+    let ext = extension(path)?;
+    if !["obj", "ply", "stl"].contains(&ext.as_str()) {
+        return Err(MeshIoError::Unsupported(format!("extension {}", ext)));
+    }
+    let mut w = BufWriter::new(File::create(path)?);
+    match ext.as_str() {
+        "obj" => write_obj(mesh, &mut w),
+        "ply" => write_ply(mesh, &mut w, PlyFormat::BinaryLittleEndian),
+        _ => write_stl(mesh, &mut w, StlFormat::Binary),
+    }
+}
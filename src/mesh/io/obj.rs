@@ -0,0 +1,185 @@
+//! Wavefront OBJ meshes.
+use super::{parse_error, MeshIoError};
+use crate::math::Scalar;
+use crate::mesh::Mesh;
+use na::{Vector2, Vector3};
+use nalgebra as na;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+/// Parse the fields of a line as floats.
+fn parse_floats(fields: &[&str], line: usize) -> Result<Vec<f64>, MeshIoError> {
+    fields
+        .iter()
+        .map(|s| {
+            s.parse::<f64>()
+                .or_else(|_| parse_error(&format!("bad number {}", s), Some(line)))
+        })
+        .collect()
+}
+
+/// Position, texture coordinate and normal indices of a face corner.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+/// Resolve a 1-based, possibly negative (relative) OBJ index.
+fn resolve_index(s: &str, n: usize, line: usize) -> Result<usize, MeshIoError> {
+    let i: i64 = s
+        .parse()
+        .or_else(|_| parse_error(&format!("bad index {}", s), Some(line)))?;
+    let i = if i < 0 { n as i64 + i } else { i - 1 };
+    if i < 0 || i >= n as i64 {
+        return parse_error(&format!("index {} out of range", s), Some(line));
+    }
+    Ok(i as usize)
+}
+
+/// Read a mesh from a Wavefront OBJ file.
+///
+/// Polygonal faces are fan-triangulated. When faces reference
+/// texture coordinates or normals, vertices are split so that each
+/// distinct combination gets its own vertex. Lines, groups and
+/// materials are ignored.
+pub fn read_obj<F: Scalar, R: BufRead>(r: &mut R) -> Result<Mesh<F>, MeshIoError> {
+    let mut positions = vec![];
+    let mut uvs = vec![];
+    let mut normals = vec![];
+    let mut faces: Vec<[Corner; 3]> = vec![];
+
+    for (line_no, line) in r.lines().enumerate() {
+        let line = line?;
+        let line_no = line_no + 1;
+        let line = line.split('#').next().unwrap_or("");
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() {
+            continue;
+        }
+        match fields[0] {
+            "v" | "vn" if fields.len() < 4 => {
+                return parse_error("expected 3 coordinates", Some(line_no));
+            }
+            "v" => {
+                let v = parse_floats(&fields[1..4], line_no)?;
+                positions.push(Vector3::new(v[0], v[1], v[2]));
+            }
+            "vn" => {
+                let v = parse_floats(&fields[1..4], line_no)?;
+                normals.push(Vector3::new(v[0], v[1], v[2]));
+            }
+            "vt" => {
+                if fields.len() < 2 {
+                    return parse_error("expected texture coordinates", Some(line_no));
+                }
+                let mut v = parse_floats(&fields[1..fields.len().min(3)], line_no)?;
+                v.resize(2, 0.0);
+                uvs.push(Vector2::new(v[0], v[1]));
+            }
+            "f" => {
+                let mut corners = vec![];
+                for corner in &fields[1..] {
+                    let mut parts = corner.split('/');
+                    let v = resolve_index(parts.next().unwrap_or(""), positions.len(), line_no)?;
+                    let vt = match parts.next() {
+                        Some(s) if !s.is_empty() => Some(resolve_index(s, uvs.len(), line_no)?),
+                        _ => None,
+                    };
+                    let vn = match parts.next() {
+                        Some(s) if !s.is_empty() => Some(resolve_index(s, normals.len(), line_no)?),
+                        _ => None,
+                    };
+                    corners.push((v, vt, vn));
+                }
+                if corners.len() < 3 {
+                    return parse_error("face with fewer than 3 vertices", Some(line_no));
+                }
+                for i in 1..corners.len() - 1 {
+                    faces.push([corners[0], corners[i], corners[i + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let has_uvs = !faces.is_empty() && faces.iter().flatten().all(|c| c.1.is_some());
+    let has_normals = !faces.is_empty() && faces.iter().flatten().all(|c| c.2.is_some());
+    let mut mesh = Mesh::default();
+
+    // attributes indexed the same as the positions need no splitting
+    let aligned = faces
+        .iter()
+        .flatten()
+        .all(|c| (!has_uvs || c.1 == Some(c.0)) && (!has_normals || c.2 == Some(c.0)))
+        && (!has_uvs || uvs.len() == positions.len())
+        && (!has_normals || normals.len() == positions.len());
+    if aligned {
+        mesh.positions = positions.iter().map(|p| na::convert(*p)).collect();
+        mesh.indices = faces.iter().flatten().map(|c| c.0 as u32).collect();
+        if has_uvs {
+            mesh.uvs = Some(uvs.iter().map(|t| na::convert(*t)).collect());
+        }
+        if has_normals {
+            mesh.normals = Some(normals.iter().map(|n| na::convert(*n)).collect());
+        }
+        return Ok(mesh);
+    }
+
+    // split vertices by their attribute combination
+    let mut vertex_map = HashMap::new();
+    let mut out_normals = vec![];
+    let mut out_uvs = vec![];
+    for c in faces.iter().flatten() {
+        let key = (c.0, c.1.filter(|_| has_uvs), c.2.filter(|_| has_normals));
+        let next = mesh.positions.len() as u32;
+        let i = *vertex_map.entry(key).or_insert_with(|| {
+            mesh.positions.push(na::convert(positions[key.0]));
+            if let Some(vt) = key.1 {
+                out_uvs.push(na::convert(uvs[vt]));
+            }
+            if let Some(vn) = key.2 {
+                out_normals.push(na::convert(normals[vn]));
+            }
+            next
+        });
+        mesh.indices.push(i);
+    }
+    if has_uvs {
+        mesh.uvs = Some(out_uvs);
+    }
+    if has_normals {
+        mesh.normals = Some(out_normals);
+    }
+    Ok(mesh)
+}
+
+/// Write a mesh as a Wavefront OBJ file, including normals and
+/// texture coordinates when the mesh has them.
+pub fn write_obj<F: Scalar, W: Write>(mesh: &Mesh<F>, w: &mut W) -> Result<(), MeshIoError> {
+    for p in &mesh.positions {
+        let (x, y, z): (f64, f64, f64) = (p.x.into(), p.y.into(), p.z.into());
+        writeln!(w, "v {} {} {}", x, y, z)?;
+    }
+    if let Some(uvs) = &mesh.uvs {
+        for t in uvs {
+            let (u, v): (f64, f64) = (t.x.into(), t.y.into());
+            writeln!(w, "vt {} {}", u, v)?;
+        }
+    }
+    if let Some(normals) = &mesh.normals {
+        for n in normals {
+            let (x, y, z): (f64, f64, f64) = (n.x.into(), n.y.into(), n.z.into());
+            writeln!(w, "vn {} {} {}", x, y, z)?;
+        }
+    }
+    let corner = |i: usize| {
+        let i = i + 1;
+        match (mesh.uvs.is_some(), mesh.normals.is_some()) {
+            (false, false) => format!("{}", i),
+            (true, false) => format!("{}/{}", i, i),
+            (false, true) => format!("{}//{}", i, i),
+            (true, true) => format!("{}/{}/{}", i, i, i),
+        }
+    };
+    for t in mesh.triangles() {
+        writeln!(w, "f {} {} {}", corner(t[0]), corner(t[1]), corner(t[2]))?;
+    }
+    Ok(())
+}
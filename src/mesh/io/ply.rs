@@ -0,0 +1,382 @@
+//! PLY meshes, in ASCII and binary.
+use super::{parse_error, MeshIoError};
+use crate::math::Scalar;
+use crate::mesh::Mesh;
+use na::{Vector2, Vector3};
+use nalgebra as na;
+use std::io::{BufRead, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PlyType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl PlyType {
+    fn parse(s: &str) -> Result<PlyType, MeshIoError> {
+        Ok(match s {
+            "char" | "int8" => PlyType::I8,
+            "uchar" | "uint8" => PlyType::U8,
+            "short" | "int16" => PlyType::I16,
+            "ushort" | "uint16" => PlyType::U16,
+            "int" | "int32" => PlyType::I32,
+            "uint" | "uint32" => PlyType::U32,
+            "float" | "float32" => PlyType::F32,
+            "double" | "float64" => PlyType::F64,
+            _ => return parse_error(&format!("unknown property type {}", s), None),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            PlyType::I8 | PlyType::U8 => 1,
+            PlyType::I16 | PlyType::U16 => 2,
+            PlyType::I32 | PlyType::U32 | PlyType::F32 => 4,
+            PlyType::F64 => 8,
+        }
+    }
+
+    /// Decode a binary value, given its bytes in big-endian order.
+    fn decode(self, b: &[u8]) -> f64 {
+        match self {
+            PlyType::I8 => b[0] as i8 as f64,
+            PlyType::U8 => b[0] as f64,
+            PlyType::I16 => i16::from_be_bytes([b[0], b[1]]) as f64,
+            PlyType::U16 => u16::from_be_bytes([b[0], b[1]]) as f64,
+            PlyType::I32 => i32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            PlyType::U32 => u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            PlyType::F32 => f32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            PlyType::F64 => f64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Property {
+    Scalar(String, PlyType),
+    /// A list, with the type of its length and of its items.
+    List(String, PlyType, PlyType),
+}
+
+impl Property {
+    fn name(&self) -> &str {
+        match self {
+            Property::Scalar(name, _) | Property::List(name, _, _) => name,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Source of values in the body of a PLY file.
+enum Values<'a, R: BufRead> {
+    Ascii {
+        r: &'a mut R,
+        tokens: std::vec::IntoIter<String>,
+    },
+    Binary {
+        r: &'a mut R,
+        big_endian: bool,
+    },
+}
+
+impl<'a, R: BufRead> Values<'a, R> {
+    fn next(&mut self, ty: PlyType) -> Result<f64, MeshIoError> {
+        match self {
+            Values::Ascii { r, tokens } => loop {
+                if let Some(t) = tokens.next() {
+                    return t
+                        .parse()
+                        .or_else(|_| parse_error(&format!("bad number {}", t), None));
+                }
+                let mut line = String::new();
+                if r.read_line(&mut line)? == 0 {
+                    return parse_error("unexpected end of file", None);
+                }
+                *tokens = line
+                    .split_whitespace()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .into_iter();
+            },
+            Values::Binary { r, big_endian } => {
+                let mut buf = [0u8; 8];
+                let b = &mut buf[..ty.size()];
+                r.read_exact(b)?;
+                if !*big_endian {
+                    b.reverse();
+                }
+                Ok(ty.decode(b))
+            }
+        }
+    }
+}
+
+/// Convert a list count or vertex index to an integer, rejecting
+/// negative and fractional values.
+fn to_index(x: f64) -> Result<u32, MeshIoError> {
+    if x >= 0.0 && x <= u32::MAX as f64 && x.fract() == 0.0 {
+        Ok(x as u32)
+    } else {
+        parse_error(&format!("bad count or index {}", x), None)
+    }
+}
+
+fn read_header<R: BufRead>(r: &mut R) -> Result<(PlyFormat, Vec<Element>), MeshIoError> {
+    let mut format = None;
+    let mut elements: Vec<Element> = vec![];
+    let mut line_no = 0;
+    loop {
+        let mut line = String::new();
+        if r.read_line(&mut line)? == 0 {
+            return parse_error("unexpected end of header", Some(line_no));
+        }
+        line_no += 1;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if line_no == 1 {
+            if fields != ["ply"] {
+                return parse_error("missing ply magic number", Some(1));
+            }
+            continue;
+        }
+        match fields.as_slice() {
+            ["end_header"] => break,
+            ["format", f, _] => {
+                format = Some(match *f {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    _ => return Err(MeshIoError::Unsupported(format!("PLY format {}", f))),
+                })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .or_else(|_| parse_error("bad element count", Some(line_no)))?,
+                properties: vec![],
+            }),
+            ["property", "list", count_ty, item_ty, name] => match elements.last_mut() {
+                Some(e) => e.properties.push(Property::List(
+                    name.to_string(),
+                    PlyType::parse(count_ty)?,
+                    PlyType::parse(item_ty)?,
+                )),
+                None => return parse_error("property before element", Some(line_no)),
+            },
+            ["property", ty, name] => match elements.last_mut() {
+                Some(e) => e
+                    .properties
+                    .push(Property::Scalar(name.to_string(), PlyType::parse(ty)?)),
+                None => return parse_error("property before element", Some(line_no)),
+            },
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => {
+                return parse_error(
+                    &format!("unknown header line {}", line.trim()),
+                    Some(line_no),
+                )
+            }
+        }
+    }
+    match format {
+        Some(f) => Ok((f, elements)),
+        None => parse_error("missing format", None),
+    }
+}
+
+/// Read a mesh from an ASCII or binary PLY file.
+///
+/// Vertex positions, normals (nx, ny, nz) and texture coordinates
+/// (s, t or u, v) are read from the vertex element, and polygons from
+/// the vertex_indices list of the face element, fan-triangulated.
+/// Other elements and properties are skipped.
+pub fn read_ply<F: Scalar, R: BufRead>(r: &mut R) -> Result<Mesh<F>, MeshIoError> {
+    let (format, elements) = read_header(r)?;
+    let mut values = match format {
+        PlyFormat::Ascii => Values::Ascii {
+            r,
+            tokens: vec![].into_iter(),
+        },
+        _ => Values::Binary {
+            r,
+            big_endian: format == PlyFormat::BinaryBigEndian,
+        },
+    };
+
+    let mut mesh = Mesh::default();
+    for e in &elements {
+        let find = |names: &[&str]| e.properties.iter().position(|p| names.contains(&p.name()));
+        let pos = [find(&["x"]), find(&["y"]), find(&["z"])];
+        let normal = [find(&["nx"]), find(&["ny"]), find(&["nz"])];
+        let uv = [
+            find(&["s", "u", "texture_u"]),
+            find(&["t", "v", "texture_v"]),
+        ];
+        let face_list = find(&["vertex_indices", "vertex_index"]);
+        if e.name == "vertex" && pos.iter().any(|p| p.is_none()) {
+            return parse_error("vertex element without x, y and z", None);
+        }
+        let has_normals = e.name == "vertex" && normal.iter().all(|p| p.is_some());
+        let has_uvs = e.name == "vertex" && uv.iter().all(|p| p.is_some());
+        if has_normals {
+            mesh.normals = Some(vec![]);
+        }
+        if has_uvs {
+            mesh.uvs = Some(vec![]);
+        }
+
+        for _ in 0..e.count {
+            let mut scalars = vec![0.0; e.properties.len()];
+            let mut list = vec![];
+            for (k, p) in e.properties.iter().enumerate() {
+                match p {
+                    Property::Scalar(_, ty) => scalars[k] = values.next(*ty)?,
+                    Property::List(_, count_ty, item_ty) => {
+                        let n = to_index(values.next(*count_ty)?)?;
+                        let items = (0..n)
+                            .map(|_| values.next(*item_ty))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        if Some(k) == face_list {
+                            list = items;
+                        }
+                    }
+                }
+            }
+            let get = |k: Option<usize>| scalars[k.unwrap()];
+
+            if e.name == "vertex" {
+                let v = Vector3::new(get(pos[0]), get(pos[1]), get(pos[2]));
+                mesh.positions.push(na::convert(v));
+                if let Some(normals) = &mut mesh.normals {
+                    let n = Vector3::new(get(normal[0]), get(normal[1]), get(normal[2]));
+                    normals.push(na::convert(n));
+                }
+                if let Some(uvs) = &mut mesh.uvs {
+                    uvs.push(na::convert(Vector2::new(get(uv[0]), get(uv[1]))));
+                }
+            } else if e.name == "face" && face_list.is_some() {
+                let list = list
+                    .into_iter()
+                    .map(to_index)
+                    .collect::<Result<Vec<_>, _>>()?;
+                for i in 1..list.len().saturating_sub(1) {
+                    for &j in &[0, i, i + 1] {
+                        mesh.indices.push(list[j]);
+                    }
+                }
+            }
+        }
+    }
+
+    let n = mesh.positions.len() as u32;
+    if mesh.indices.iter().any(|&i| i >= n) {
+        return parse_error("face index out of range", None);
+    }
+    Ok(mesh)
+}
+
+/// Write a mesh as a PLY file, including normals and texture
+/// coordinates when the mesh has them.
+///
+/// Values are written as floats, and faces with uchar counts and int
+/// indices.
+pub fn write_ply<F: Scalar, W: Write>(
+    mesh: &Mesh<F>,
+    w: &mut W,
+    format: PlyFormat,
+) -> Result<(), MeshIoError> {
+    writeln!(w, "ply")?;
+    writeln!(
+        w,
+        "format {} 1.0",
+        match format {
+            PlyFormat::Ascii => "ascii",
+            PlyFormat::BinaryLittleEndian => "binary_little_endian",
+            PlyFormat::BinaryBigEndian => "binary_big_endian",
+        }
+    )?;
+    writeln!(w, "element vertex {}", mesh.num_vertices())?;
+    let mut names = vec!["x", "y", "z"];
+    if mesh.normals.is_some() {
+        names.extend_from_slice(&["nx", "ny", "nz"]);
+    }
+    if mesh.uvs.is_some() {
+        names.extend_from_slice(&["s", "t"]);
+    }
+    for name in &names {
+        writeln!(w, "property float {}", name)?;
+    }
+    writeln!(w, "element face {}", mesh.num_triangles())?;
+    writeln!(w, "property list uchar int vertex_indices")?;
+    writeln!(w, "end_header")?;
+
+    for i in 0..mesh.num_vertices() {
+        let mut row: Vec<F> = mesh.positions[i].iter().copied().collect();
+        if let Some(normals) = &mesh.normals {
+            row.extend(normals[i].iter());
+        }
+        if let Some(uvs) = &mesh.uvs {
+            row.extend(uvs[i].iter());
+        }
+        let row: Vec<f32> = row
+            .into_iter()
+            .map(|x| {
+                let x: f64 = x.into();
+                x as f32
+            })
+            .collect();
+        match format {
+            PlyFormat::Ascii => {
+                let s: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+                writeln!(w, "{}", s.join(" "))?;
+            }
+            PlyFormat::BinaryLittleEndian => {
+                for x in row {
+                    w.write_all(&x.to_le_bytes())?;
+                }
+            }
+            PlyFormat::BinaryBigEndian => {
+                for x in row {
+                    w.write_all(&x.to_be_bytes())?;
+                }
+            }
+        }
+    }
+    for t in mesh.indices.chunks_exact(3) {
+        match format {
+            PlyFormat::Ascii => writeln!(w, "3 {} {} {}", t[0], t[1], t[2])?,
+            PlyFormat::BinaryLittleEndian => {
+                w.write_all(&[3])?;
+                for &i in t {
+                    w.write_all(&(i as i32).to_le_bytes())?;
+                }
+            }
+            PlyFormat::BinaryBigEndian => {
+                w.write_all(&[3])?;
+                for &i in t {
+                    w.write_all(&(i as i32).to_be_bytes())?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,159 @@
+//! STL meshes, in ASCII and binary.
+use super::{parse_error, MeshIoError};
+use crate::math::Scalar;
+use crate::mesh::Mesh;
+use na::Vector3;
+use nalgebra as na;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StlFormat {
+    Ascii,
+    Binary,
+}
+
+/// Build a mesh from triangle soup, merging vertices with identical
+/// coordinates.
+fn merge_vertices<F: Scalar>(corners: &[Vector3<f64>]) -> Mesh<F> {
+    let mut mesh = Mesh::default();
+    let mut index = HashMap::new();
+    for c in corners {
+        // treat -0.0 as 0.0
+        let key = (
+            (c.x + 0.0).to_bits(),
+            (c.y + 0.0).to_bits(),
+            (c.z + 0.0).to_bits(),
+        );
+        let next = mesh.positions.len() as u32;
+        let i = *index.entry(key).or_insert_with(|| {
+            mesh.positions.push(na::convert(*c));
+            next
+        });
+        mesh.indices.push(i);
+    }
+    mesh
+}
+
+fn read_binary(data: &[u8]) -> Result<Vec<Vector3<f64>>, MeshIoError> {
+    if data.len() < 84 {
+        return parse_error("binary STL shorter than its header", None);
+    }
+    let n = u32::from_le_bytes([data[80], data[81], data[82], data[83]]) as usize;
+    if data.len() < 84 + 50 * n {
+        return parse_error("binary STL shorter than its triangle count", None);
+    }
+    let float =
+        |o: usize| f32::from_le_bytes([data[o], data[o + 1], data[o + 2], data[o + 3]]) as f64;
+    let mut corners = Vec::with_capacity(3 * n);
+    for t in 0..n {
+        // skip the normal
+        let o = 84 + 50 * t + 12;
+        for k in 0..3 {
+            let c = o + 12 * k;
+            corners.push(Vector3::new(float(c), float(c + 4), float(c + 8)));
+        }
+    }
+    Ok(corners)
+}
+
+fn read_ascii(data: &[u8]) -> Result<Vec<Vector3<f64>>, MeshIoError> {
+    let text =
+        std::str::from_utf8(data).or_else(|_| parse_error("ASCII STL is not valid UTF-8", None))?;
+    let mut corners = vec![];
+    for (line_no, line) in text.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.first() != Some(&"vertex") {
+            continue;
+        }
+        if fields.len() != 4 {
+            return parse_error("expected 3 coordinates", Some(line_no + 1));
+        }
+        let mut v = [0.0; 3];
+        for (x, s) in v.iter_mut().zip(&fields[1..]) {
+            *x = s
+                .parse()
+                .or_else(|_| parse_error(&format!("bad number {}", s), Some(line_no + 1)))?;
+        }
+        corners.push(Vector3::new(v[0], v[1], v[2]));
+    }
+    if corners.len() % 3 != 0 {
+        return parse_error("facet without 3 vertices", None);
+    }
+    Ok(corners)
+}
+
+/// Read a mesh from an ASCII or binary STL file.
+///
+/// STL files store each triangle separately, so vertices with
+/// identical coordinates are merged. Facet normals are ignored.
+pub fn read_stl<F: Scalar, R: BufRead>(r: &mut R) -> Result<Mesh<F>, MeshIoError> {
+    let mut data = vec![];
+    r.read_to_end(&mut data)?;
+
+    // binary files may also start with "solid", so check whether the
+    // size matches the triangle count
+    let binary_size = if data.len() >= 84 {
+        Some(84 + 50 * u32::from_le_bytes([data[80], data[81], data[82], data[83]]) as usize)
+    } else {
+        None
+    };
+    let corners = if !data.starts_with(b"solid") || binary_size == Some(data.len()) {
+        read_binary(&data)?
+    } else {
+        read_ascii(&data)?
+    };
+    Ok(merge_vertices(&corners))
+}
+
+/// Write a mesh as an STL file, with facet normals computed from the
+/// triangle winding.
+pub fn write_stl<F: Scalar, W: Write>(
+    mesh: &Mesh<F>,
+    w: &mut W,
+    format: StlFormat,
+) -> Result<(), MeshIoError> {
+    let to_f64 =
+        |v: &Vector3<F>| -> Vector3<f64> { Vector3::new(v.x.into(), v.y.into(), v.z.into()) };
+    let triangles = mesh.triangles().map(|t| {
+        let p = [
+            to_f64(&mesh.positions[t[0]]),
+            to_f64(&mesh.positions[t[1]]),
+            to_f64(&mesh.positions[t[2]]),
+        ];
+        let n = (p[1] - p[0])
+            .cross(&(p[2] - p[0]))
+            .try_normalize(0.0)
+            .unwrap_or_else(Vector3::zeros);
+        (n, p)
+    });
+
+    match format {
+        StlFormat::Ascii => {
+            writeln!(w, "solid mesh")?;
+            for (n, p) in triangles {
+                writeln!(w, "facet normal {} {} {}", n.x, n.y, n.z)?;
+                writeln!(w, "  outer loop")?;
+                for v in &p {
+                    writeln!(w, "    vertex {} {} {}", v.x, v.y, v.z)?;
+                }
+                writeln!(w, "  endloop")?;
+                writeln!(w, "endfacet")?;
+            }
+            writeln!(w, "endsolid mesh")?;
+        }
+        StlFormat::Binary => {
+            w.write_all(&[0; 80])?;
+            w.write_all(&(mesh.num_triangles() as u32).to_le_bytes())?;
+            for (n, p) in triangles {
+                for v in std::iter::once(&n).chain(p.iter()) {
+                    for x in v.iter() {
+                        w.write_all(&(*x as f32).to_le_bytes())?;
+                    }
+                }
+                w.write_all(&[0; 2])?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,181 @@
+//! Indexed triangle meshes.
+use crate::math::{Aabb, Scalar};
+use na::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3};
+use nalgebra as na;
+use std::collections::HashMap;
+
+mod bvh;
+mod edges;
+pub mod io;
+mod primitives;
+mod slice;
+pub mod subdivide;
+
+pub use bvh::{Bvh, MeshHit, NearestPoint};
+pub use edges::{EdgeAdjacency, EdgeKind, MeshEdge};
+pub use slice::{slice_triangles, Contours};
+pub use subdivide::{catmull_clark, loop_subdivide, quads_to_triangles};
+
+/// Map each vertex to the first vertex with an identical position.
+pub(crate) fn weld_positions<F: Scalar>(positions: &[Vector3<F>]) -> Vec<u32> {
+    let mut first = HashMap::new();
+    positions
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let bits = |x: F| {
+                let x: f64 = x.into();
+                // treat -0.0 as 0.0
+                (x + 0.0).to_bits()
+            };
+            *first
+                .entry((bits(p.x), bits(p.y), bits(p.z)))
+                .or_insert(i as u32)
+        })
+        .collect()
+}
+
+/// An indexed triangle mesh.
+///
+/// `indices` holds three vertex indices per triangle, wound
+/// counter-clockwise when seen from the outside. Normals and UVs,
+/// when present, have one entry per position.
+#[derive(Clone, Debug)]
+pub struct Mesh<F: Scalar> {
+    pub positions: Vec<Vector3<F>>,
+    pub normals: Option<Vec<Vector3<F>>>,
+    pub uvs: Option<Vec<Vector2<F>>>,
+    pub indices: Vec<u32>,
+}
+
+impl<F: Scalar> Default for Mesh<F> {
+    fn default() -> Self {
+        Mesh::new(vec![], vec![])
+    }
+}
+
+impl<F: Scalar> Mesh<F> {
+    /// Create a mesh from positions and triangle indices, without
+    /// normals or UVs.
+    pub fn new(positions: Vec<Vector3<F>>, indices: Vec<u32>) -> Mesh<F> {
+        Mesh {
+            positions,
+            normals: None,
+            uvs: None,
+            indices,
+        }
+    }
+
+    pub fn num_vertices(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn num_triangles(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Iterate over the vertex indices of each triangle.
+    pub fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.indices
+            .chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+    }
+
+    /// Return the positions of the vertices of triangle `i`.
+    pub fn triangle_positions(&self, i: usize) -> [Point3<F>; 3] {
+        let t = &self.indices[3 * i..3 * i + 3];
+        [
+            Point3::from(self.positions[t[0] as usize]),
+            Point3::from(self.positions[t[1] as usize]),
+            Point3::from(self.positions[t[2] as usize]),
+        ]
+    }
+
+    /// Return the bounding box of the vertices, or `None` for an
+    /// empty mesh.
+    pub fn bounds(&self) -> Option<Aabb<F>> {
+        let points: Vec<_> = self.positions.iter().map(|p| Point3::from(*p)).collect();
+        Aabb::bounding(&points)
+    }
+
+    /// Append the vertices and triangles of another mesh.
+    ///
+    /// Normals and UVs are kept only if both meshes have them.
+    pub fn append(&mut self, other: &Mesh<F>) {
+        if self.positions.is_empty() {
+            self.normals = other.normals.clone();
+            self.uvs = other.uvs.clone();
+        } else {
+            match (&mut self.normals, &other.normals) {
+                (Some(a), Some(b)) => a.extend_from_slice(b),
+                _ => self.normals = None,
+            }
+            match (&mut self.uvs, &other.uvs) {
+                (Some(a), Some(b)) => a.extend_from_slice(b),
+                _ => self.uvs = None,
+            }
+        }
+        let offset = self.positions.len() as u32;
+        self.positions.extend_from_slice(&other.positions);
+        self.indices
+            .extend(other.indices.iter().map(|i| i + offset));
+    }
+
+    /// Reverse the winding of every triangle, and the normals.
+    pub fn flip(&mut self) {
+        for t in self.indices.chunks_exact_mut(3) {
+            t.swap(1, 2);
+        }
+        if let Some(normals) = &mut self.normals {
+            for n in normals.iter_mut() {
+                *n = -*n;
+            }
+        }
+    }
+
+    /// Set the normals to the area-weighted average of the normals of
+    /// the adjacent triangles.
+    pub fn compute_normals(&mut self) {
+        self.normals = Some(self.vertex_normals());
+    }
+
+    /// Apply an affine transform to the mesh.
+    ///
+    /// Normals are transformed by the inverse transpose. Transforms
+    /// that mirror the mesh also reverse the triangle winding, so that
+    /// triangles still face outward.
+    pub fn transform(&mut self, m: &Matrix4<F>) {
+        for p in self.positions.iter_mut() {
+            *p = m.transform_point(&Point3::from(*p)).coords;
+        }
+        let linear = m.fixed_slice::<na::U3, na::U3>(0, 0).into_owned();
+        if let Some(normals) = &mut self.normals {
+            let nm = linear
+                .try_inverse()
+                .map(|i| i.transpose())
+                .unwrap_or(linear);
+            for n in normals.iter_mut() {
+                *n = (nm * *n).try_normalize(F::zero()).unwrap_or(*n);
+            }
+        }
+        if linear.determinant() < F::zero() {
+            for t in self.indices.chunks_exact_mut(3) {
+                t.swap(1, 2);
+            }
+        }
+    }
+
+    pub fn translate(&mut self, v: &Vector3<F>) {
+        self.transform(&Matrix4::new_translation(v));
+    }
+
+    pub fn rotate(&mut self, q: &UnitQuaternion<F>) {
+        self.transform(&q.to_homogeneous());
+    }
+
+    /// Scale the mesh about the origin, by separate factors along
+    /// each axis.
+    pub fn scale(&mut self, s: &Vector3<F>) {
+        self.transform(&Matrix4::new_nonuniform_scaling(s));
+    }
+}
@@ -0,0 +1,323 @@
+//! Generators for common mesh primitives.
+//!
+//! Primitives are centered at the origin. Round primitives are built
+//! around the y axis, matching the default camera up direction.
+use super::Mesh;
+use crate::math::Scalar;
+use crate::models::add_box;
+use na::{Vector2, Vector3};
+use nalgebra as na;
+
+/// A point on the profile of a surface of revolution, as a distance
+/// from the y axis, a height, the profile normal in the same (r, y)
+/// coordinates, and the v texture coordinate.
+struct ProfilePoint<F: Scalar> {
+    r: F,
+    y: F,
+    normal: Vector2<F>,
+    v: F,
+}
+
+impl<F: Scalar> ProfilePoint<F> {
+    fn new(r: F, y: F, normal: Vector2<F>, v: F) -> ProfilePoint<F> {
+        ProfilePoint { r, y, normal, v }
+    }
+}
+
+fn fraction<F: Scalar>(i: usize, n: usize) -> F {
+    na::convert(i as f64 / n as f64)
+}
+
+impl<F: Scalar> Mesh<F> {
+    /// Append the surface made by revolving the profile around the y
+    /// axis, with `segments` divisions around the axis.
+    ///
+    /// For outward-facing triangles, the profile normal must be to
+    /// the left of the direction of the profile, e.g. a profile going
+    /// down for the side of a cylinder. The seam is duplicated so
+    /// that u runs from 0 to 1 around the axis. Triangles collapsed
+    /// on the axis are dropped.
+    fn add_revolution(&mut self, profile: &[ProfilePoint<F>], segments: usize) {
+        let mut m = Mesh {
+            normals: Some(vec![]),
+            uvs: Some(vec![]),
+            ..Mesh::default()
+        };
+        // rows on the axis, up to rounding in the profile, are snapped
+        // onto it so that they share exact positions
+        let max_r = profile.iter().fold(F::zero(), |r, p| r.max(p.r.abs()));
+        let tol = max_r * F::default_epsilon() * na::convert(16.0);
+        let on_axis: Vec<bool> = profile.iter().map(|p| p.r.abs() <= tol).collect();
+
+        let cols = segments + 1;
+        for (p, &axial) in profile.iter().zip(&on_axis) {
+            let r = if axial { F::zero() } else { p.r };
+            for j in 0..cols {
+                let u: F = fraction(j, segments);
+                // the seam is at exactly the same positions on both sides
+                let (s, c) = if j == segments {
+                    (F::zero(), F::one())
+                } else {
+                    (u * F::two_pi()).sin_cos()
+                };
+                m.positions.push(Vector3::new(r * s, p.y, r * c));
+                if let Some(normals) = &mut m.normals {
+                    normals.push(Vector3::new(p.normal.x * s, p.normal.y, p.normal.x * c));
+                }
+                if let Some(uvs) = &mut m.uvs {
+                    uvs.push(Vector2::new(u, p.v));
+                }
+            }
+        }
+        for k in 0..profile.len().saturating_sub(1) {
+            for j in 0..segments {
+                let a = (k * cols + j) as u32;
+                let (b, c, d) = (a + 1, a + cols as u32, a + cols as u32 + 1);
+                if !on_axis[k] {
+                    m.indices.extend_from_slice(&[a, c, b]);
+                }
+                if !on_axis[k + 1] {
+                    m.indices.extend_from_slice(&[b, c, d]);
+                }
+            }
+        }
+        self.append(&m);
+    }
+
+    /// Create a box with the given center and half-widths, sharing
+    /// vertices between faces as in `add_box`. The box has no normals
+    /// or UVs.
+    pub fn cuboid(center: &Vector3<F>, half_widths: &Vector3<F>) -> Mesh<F> {
+        let mut m = Mesh::default();
+        add_box(&mut m.positions, &mut m.indices, *center, *half_widths);
+        m
+    }
+
+    /// Create a sphere from `segments` divisions around the y axis
+    /// and `rings` divisions from pole to pole.
+    pub fn uv_sphere(radius: F, segments: usize, rings: usize) -> Mesh<F> {
+        let profile: Vec<_> = (0..=rings)
+            .map(|k| {
+                let v: F = fraction(k, rings);
+                let (s, c) = (v * F::pi()).sin_cos();
+                ProfilePoint::new(radius * s, radius * c, Vector2::new(s, c), v)
+            })
+            .collect();
+        let mut m = Mesh::default();
+        m.add_revolution(&profile, segments);
+        m
+    }
+
+    /// Create a sphere by repeatedly subdividing an icosahedron.
+    ///
+    /// The sphere has normals, but no UVs.
+    pub fn icosphere(radius: F, subdivisions: usize) -> Mesh<F> {
+        let t = (1.0 + 5f64.sqrt()) / 2.0;
+        let mut positions: Vec<Vector3<f64>> = [
+            (-1.0, t, 0.0),
+            (1.0, t, 0.0),
+            (-1.0, -t, 0.0),
+            (1.0, -t, 0.0),
+            (0.0, -1.0, t),
+            (0.0, 1.0, t),
+            (0.0, -1.0, -t),
+            (0.0, 1.0, -t),
+            (t, 0.0, -1.0),
+            (t, 0.0, 1.0),
+            (-t, 0.0, -1.0),
+            (-t, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|&(x, y, z)| Vector3::new(x, y, z).normalize())
+        .collect();
+        let mut indices: Vec<u32> = vec![
+            0, 11, 5, 0, 5, 1, 0, 1, 7, 0, 7, 10, 0, 10, 11, 1, 5, 9, 5, 11, 4, 11, 10, 2, 10, 7,
+            6, 7, 1, 8, 3, 9, 4, 3, 4, 2, 3, 2, 6, 3, 6, 8, 3, 8, 9, 4, 9, 5, 2, 4, 11, 6, 2, 10,
+            8, 6, 7, 9, 8, 1,
+        ];
+
+        for _ in 0..subdivisions {
+            let mut midpoints = std::collections::HashMap::new();
+            let mut midpoint = |a: u32, b: u32| {
+                let key = (a.min(b), a.max(b));
+                *midpoints.entry(key).or_insert_with(|| {
+                    let p = (positions[a as usize] + positions[b as usize]).normalize();
+                    positions.push(p);
+                    positions.len() as u32 - 1
+                })
+            };
+            let mut next = Vec::with_capacity(indices.len() * 4);
+            for t in indices.chunks_exact(3) {
+                let (a, b, c) = (t[0], t[1], t[2]);
+                let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                next.extend_from_slice(&[a, ab, ca, b, bc, ab, c, ca, bc, ab, bc, ca]);
+            }
+            indices = next;
+        }
+
+        let normals: Vec<Vector3<F>> = positions.iter().map(|p| na::convert(*p)).collect();
+        Mesh {
+            positions: normals.iter().map(|n| n * radius).collect(),
+            normals: Some(normals),
+            uvs: None,
+            indices,
+        }
+    }
+
+    /// Create a capped cylinder along the y axis.
+    pub fn cylinder(radius: F, height: F, segments: usize) -> Mesh<F> {
+        let h = height * na::convert(0.5);
+        let (zero, one) = (F::zero(), F::one());
+        let (up, side, down) = (
+            Vector2::new(zero, one),
+            Vector2::new(one, zero),
+            Vector2::new(zero, -one),
+        );
+        let mut m = Mesh::default();
+        m.add_revolution(
+            &[
+                ProfilePoint::new(zero, h, up, zero),
+                ProfilePoint::new(radius, h, up, one),
+            ],
+            segments,
+        );
+        m.add_revolution(
+            &[
+                ProfilePoint::new(radius, h, side, zero),
+                ProfilePoint::new(radius, -h, side, one),
+            ],
+            segments,
+        );
+        m.add_revolution(
+            &[
+                ProfilePoint::new(radius, -h, down, zero),
+                ProfilePoint::new(zero, -h, down, one),
+            ],
+            segments,
+        );
+        m
+    }
+
+    /// Create a capped cone along the y axis, with the apex at the
+    /// top.
+    pub fn cone(radius: F, height: F, segments: usize) -> Mesh<F> {
+        let h = height * na::convert(0.5);
+        let (zero, one) = (F::zero(), F::one());
+        let slope = Vector2::new(height, radius).normalize();
+        let down = Vector2::new(zero, -one);
+        let mut m = Mesh::default();
+        m.add_revolution(
+            &[
+                ProfilePoint::new(zero, h, slope, zero),
+                ProfilePoint::new(radius, -h, slope, one),
+            ],
+            segments,
+        );
+        m.add_revolution(
+            &[
+                ProfilePoint::new(radius, -h, down, zero),
+                ProfilePoint::new(zero, -h, down, one),
+            ],
+            segments,
+        );
+        m
+    }
+
+    /// Create a torus around the y axis, with `major_segments`
+    /// divisions around the axis and `minor_segments` around the
+    /// tube.
+    pub fn torus(
+        major_radius: F,
+        minor_radius: F,
+        major_segments: usize,
+        minor_segments: usize,
+    ) -> Mesh<F> {
+        let profile: Vec<_> = (0..=minor_segments)
+            .map(|k| {
+                let v: F = fraction(k, minor_segments);
+                // close the tube seam at exactly the starting point
+                let (s, c) = if k == minor_segments {
+                    (F::zero(), F::one())
+                } else {
+                    (v * F::two_pi()).sin_cos()
+                };
+                ProfilePoint::new(
+                    major_radius + minor_radius * c,
+                    -minor_radius * s,
+                    Vector2::new(c, -s),
+                    v,
+                )
+            })
+            .collect();
+        let mut m = Mesh::default();
+        m.add_revolution(&profile, major_segments);
+        m
+    }
+
+    /// Create a capsule along the y axis: a cylinder of the given
+    /// length capped by hemispheres, each with `rings` divisions.
+    pub fn capsule(radius: F, length: F, segments: usize, rings: usize) -> Mesh<F> {
+        let h = length * na::convert(0.5);
+        let total = length + radius * F::pi();
+        let mut profile = vec![];
+        for (offset, start) in [(h, 0), (-h, rings)].iter() {
+            for k in 0..=rings {
+                let angle = F::frac_pi_2() * fraction(k + start, rings);
+                let (s, c) = angle.sin_cos();
+                // arc length from the top pole
+                let mut dist = radius * angle;
+                if *offset < F::zero() {
+                    dist += length;
+                }
+                profile.push(ProfilePoint::new(
+                    radius * s,
+                    *offset + radius * c,
+                    Vector2::new(s, c),
+                    dist / total,
+                ));
+            }
+        }
+        if length == F::zero() {
+            profile.remove(rings + 1);
+        }
+        let mut m = Mesh::default();
+        m.add_revolution(&profile, segments);
+        m
+    }
+
+    /// Create a flat grid in the xz plane, facing +y, with `nx` by
+    /// `nz` cells.
+    ///
+    /// Vertex (i, j), for i in 0..=nx and j in 0..=nz, is at index
+    /// j * (nx + 1) + i.
+    pub fn plane_grid(width: F, depth: F, nx: usize, nz: usize) -> Mesh<F> {
+        let half: F = na::convert(0.5);
+        let mut m = Mesh {
+            normals: Some(vec![Vector3::y(); (nx + 1) * (nz + 1)]),
+            uvs: Some(vec![]),
+            ..Mesh::default()
+        };
+        for j in 0..=nz {
+            for i in 0..=nx {
+                let (u, v): (F, F) = (fraction(i, nx), fraction(j, nz));
+                m.positions.push(Vector3::new(
+                    (u - half) * width,
+                    F::zero(),
+                    (v - half) * depth,
+                ));
+                if let Some(uvs) = &mut m.uvs {
+                    uvs.push(Vector2::new(u, v));
+                }
+            }
+        }
+        let row = nx as u32 + 1;
+        for j in 0..nz as u32 {
+            for i in 0..nx as u32 {
+                let a = j * row + i;
+                let (b, c, d) = (a + 1, a + row, a + row + 1);
+                m.indices.extend_from_slice(&[a, c, b, b, c, d]);
+            }
+        }
+        m
+    }
+}
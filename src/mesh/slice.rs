@@ -0,0 +1,162 @@
+//! Slicing triangle meshes with planes into contour polylines.
+use super::{weld_positions, Mesh};
+use crate::math::Scalar;
+use na::{Point3, Vector3, Vector4};
+use nalgebra as na;
+use std::collections::{HashMap, HashSet};
+
+/// A point where the plane crosses a mesh edge, keyed by the welded
+/// vertices of the edge, smallest first.
+type EdgeKey = (u32, u32);
+
+/// The contours of one slice, as polylines.
+pub type Contours<F> = Vec<Vec<Point3<F>>>;
+
+/// Join directed segments, each from one edge crossing to another,
+/// into polylines.
+///
+/// Chains that start at a crossing no segment ends on are walked
+/// first, so open contours come out whole, then the remaining closed
+/// loops.
+fn join_segments<F: Scalar>(
+    segments: &[(EdgeKey, EdgeKey)],
+    points: &HashMap<EdgeKey, Point3<F>>,
+) -> Contours<F> {
+    let mut next: HashMap<EdgeKey, usize> = HashMap::new();
+    let mut ends = HashSet::new();
+    for (i, (start, end)) in segments.iter().enumerate() {
+        next.insert(*start, i);
+        ends.insert(*end);
+    }
+    let heads = (0..segments.len()).filter(|&i| !ends.contains(&segments[i].0));
+    let order: Vec<usize> = heads.chain(0..segments.len()).collect();
+
+    let mut used = vec![false; segments.len()];
+    let mut contours = vec![];
+    for first in order {
+        if used[first] {
+            continue;
+        }
+        let mut keys = vec![segments[first].0];
+        let mut i = first;
+        loop {
+            used[i] = true;
+            let end = segments[i].1;
+            keys.push(end);
+            match next.get(&end) {
+                Some(&j) if !used[j] => i = j,
+                _ => break,
+            }
+        }
+
+        let mut contour: Vec<Point3<F>> = vec![];
+        for k in &keys {
+            let p = points[k];
+            // crossings at a vertex on the plane repeat points
+            if contour.last() != Some(&p) {
+                contour.push(p);
+            }
+        }
+        if contour.len() > 1 {
+            contours.push(contour);
+        }
+    }
+    contours
+}
+
+/// Intersect a triangle mesh with the plane (a, b, c, d), where
+/// ax + by + cz + d = 0, and return the contours as polylines.
+///
+/// Vertices with identical positions are treated as the same vertex,
+/// so seams from duplicated vertices don't split contours. Closed
+/// contours repeat their first point at the end. For a closed mesh
+/// with outward-facing triangles, contours run counter-clockwise
+/// around the solid when seen from the positive side of the plane,
+/// and clockwise around holes.
+///
+/// Vertices exactly on the plane count as being on its positive side,
+/// so faces lying in the plane are not part of any contour.
+pub fn slice_triangles<F: Scalar>(
+    positions: &[Vector3<F>],
+    triangles: &[u32],
+    plane: &Vector4<F>,
+) -> Contours<F> {
+    let canonical = weld_positions(positions);
+    let dist: Vec<F> = positions
+        .iter()
+        .map(|p| plane.xyz().dot(p) + plane[3])
+        .collect();
+    let above = |v: u32| dist[v as usize] >= F::zero();
+
+    let mut points = HashMap::new();
+    let mut crossing = |a: u32, b: u32| -> EdgeKey {
+        let (a, b) = (canonical[a as usize], canonical[b as usize]);
+        let key = (a.min(b), a.max(b));
+        points.entry(key).or_insert_with(|| {
+            // interpolate in a fixed order, so the point is the same
+            // from either side of the edge
+            let (pa, pb) = (positions[key.0 as usize], positions[key.1 as usize]);
+            let (da, db) = (dist[key.0 as usize], dist[key.1 as usize]);
+            Point3::from(pa + (pb - pa) * (da / (da - db)))
+        });
+        key
+    };
+
+    let mut segments = vec![];
+    for t in triangles.chunks_exact(3) {
+        let mut start = None;
+        let mut end = None;
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            match (above(a), above(b)) {
+                (true, false) => start = Some(crossing(a, b)),
+                (false, true) => end = Some(crossing(a, b)),
+                _ => {}
+            }
+        }
+        // walking a triangle counter-clockwise, the contour runs from
+        // where the boundary goes below the plane to where it comes
+        // back above
+        if let (Some(start), Some(end)) = (start, end) {
+            if start != end {
+                segments.push((start, end));
+            }
+        }
+    }
+    join_segments(&segments, &points)
+}
+
+impl<F: Scalar> Mesh<F> {
+    /// Intersect the mesh with a plane, as in `slice_triangles`.
+    pub fn slice(&self, plane: &Vector4<F>) -> Contours<F> {
+        slice_triangles(&self.positions, &self.indices, plane)
+    }
+
+    /// Intersect the mesh with the parallel planes n · p = offset for
+    /// each offset, returning the contours of each slice.
+    pub fn slice_parallel(&self, normal: &Vector3<F>, offsets: &[F]) -> Vec<Contours<F>> {
+        offsets
+            .iter()
+            .map(|&o| self.slice(&Vector4::new(normal.x, normal.y, normal.z, -o)))
+            .collect()
+    }
+
+    /// Intersect the mesh with `count` evenly spaced planes along
+    /// `normal`, strictly between the extremes of the mesh in that
+    /// direction. Returns the offsets of the planes, as in
+    /// `slice_parallel`, and the contours of each slice.
+    pub fn slice_layers(&self, normal: &Vector3<F>, count: usize) -> (Vec<F>, Vec<Contours<F>>) {
+        let along: Vec<F> = self.positions.iter().map(|p| normal.dot(p)).collect();
+        let (lo, hi) = match along.first() {
+            Some(&x) => along
+                .iter()
+                .fold((x, x), |(lo, hi), &y| (lo.min(y), hi.max(y))),
+            None => return (vec![], vec![]),
+        };
+        let offsets: Vec<F> = (1..=count)
+            .map(|i| lo + (hi - lo) * na::convert::<f64, F>(i as f64 / (count + 1) as f64))
+            .collect();
+        let slices = self.slice_parallel(normal, &offsets);
+        (offsets, slices)
+    }
+}
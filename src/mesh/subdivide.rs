@@ -0,0 +1,307 @@
+//! Loop and Catmull-Clark subdivision surfaces.
+//!
+//! Both schemes support hard creases: edges listed as creases, and
+//! boundary edges, are subdivided as cubic B-spline curves, and
+//! vertices with three or more crease edges stay fixed as corners.
+use super::{weld_positions, Mesh};
+use crate::math::Scalar;
+use na::Vector3;
+use nalgebra as na;
+use std::collections::{HashMap, HashSet};
+
+/// Vertices, face indices and creases after a subdivision step.
+type Subdivided<F> = (Vec<Vector3<F>>, Vec<u32>, HashSet<(u32, u32)>);
+
+fn edge_key(a: u32, b: u32) -> (u32, u32) {
+    (a.min(b), a.max(b))
+}
+
+/// The unique edges of a polygon mesh with faces of a fixed size.
+struct Edges {
+    /// Index of each edge by its sorted vertex pair.
+    index: HashMap<(u32, u32), usize>,
+    vertices: Vec<(u32, u32)>,
+    faces: Vec<Vec<usize>>,
+    sharp: Vec<bool>,
+}
+
+impl Edges {
+    fn new(faces: &[u32], face_size: usize, creases: &HashSet<(u32, u32)>) -> Edges {
+        let mut edges = Edges {
+            index: HashMap::new(),
+            vertices: vec![],
+            faces: vec![],
+            sharp: vec![],
+        };
+        for (fi, f) in faces.chunks_exact(face_size).enumerate() {
+            for k in 0..face_size {
+                let key = edge_key(f[k], f[(k + 1) % face_size]);
+                let n = edges.vertices.len();
+                let ei = *edges.index.entry(key).or_insert(n);
+                if ei == n {
+                    edges.vertices.push(key);
+                    edges.faces.push(vec![]);
+                }
+                edges.faces[ei].push(fi);
+            }
+        }
+        edges.sharp = edges
+            .vertices
+            .iter()
+            .zip(&edges.faces)
+            .map(|(key, faces)| faces.len() != 2 || creases.contains(key))
+            .collect();
+        edges
+    }
+
+    fn get(&self, a: u32, b: u32) -> usize {
+        self.index[&edge_key(a, b)]
+    }
+
+    /// Split each crease into its two halves, given the index of the
+    /// first edge point.
+    fn split_creases(&self, creases: &HashSet<(u32, u32)>, first: u32) -> HashSet<(u32, u32)> {
+        let mut out = HashSet::new();
+        for &(a, b) in creases {
+            if let Some(&e) = self.index.get(&(a, b)) {
+                let m = first + e as u32;
+                out.insert(edge_key(a, m));
+                out.insert(edge_key(m, b));
+            }
+        }
+        out
+    }
+
+    /// Return the vertex points, given the rule for smooth vertices.
+    ///
+    /// The smooth rule gets a vertex and the edges around it.
+    fn vertex_points<F, S>(&self, positions: &[Vector3<F>], smooth: S) -> Vec<Vector3<F>>
+    where
+        F: Scalar,
+        S: Fn(usize, &[usize]) -> Vector3<F>,
+    {
+        let mut around = vec![vec![]; positions.len()];
+        for (e, &(a, b)) in self.vertices.iter().enumerate() {
+            around[a as usize].push(e);
+            around[b as usize].push(e);
+        }
+        let other = |e: usize, v: usize| {
+            let (a, b) = self.vertices[e];
+            if a as usize == v {
+                b as usize
+            } else {
+                a as usize
+            }
+        };
+        (0..positions.len())
+            .map(|v| {
+                let sharp: Vec<usize> = around[v]
+                    .iter()
+                    .copied()
+                    .filter(|&e| self.sharp[e])
+                    .collect();
+                match sharp.len() {
+                    0 | 1 if !around[v].is_empty() => smooth(v, &around[v]),
+                    2 => {
+                        let (s0, s1) = (other(sharp[0], v), other(sharp[1], v));
+                        positions[v] * na::convert::<f64, F>(0.75)
+                            + (positions[s0] + positions[s1]) * na::convert::<f64, F>(0.125)
+                    }
+                    // corners and isolated vertices
+                    _ => positions[v],
+                }
+            })
+            .collect()
+    }
+}
+
+/// Apply one level of Loop subdivision to a triangle mesh.
+fn loop_step<F: Scalar>(
+    positions: &[Vector3<F>],
+    triangles: &[u32],
+    creases: &HashSet<(u32, u32)>,
+) -> Subdivided<F> {
+    let edges = Edges::new(triangles, 3, creases);
+    let p = |i: u32| positions[i as usize];
+    let c = |x: f64| na::convert::<f64, F>(x);
+
+    let mut out = edges.vertex_points(positions, |v, around| {
+        let n = around.len();
+        let sum = around.iter().fold(Vector3::zeros(), |acc, &e| {
+            let (a, b) = edges.vertices[e];
+            acc + p(if a as usize == v { b } else { a })
+        });
+        let cos: f64 = 0.375 + 0.25 * (std::f64::consts::PI * 2.0 / n as f64).cos();
+        let beta = (0.625 - cos * cos) / n as f64;
+        positions[v] * c(1.0 - n as f64 * beta) + sum * c(beta)
+    });
+
+    let first = positions.len() as u32;
+    for (e, &(a, b)) in edges.vertices.iter().enumerate() {
+        let mid = (p(a) + p(b)) * c(0.5);
+        out.push(if edges.sharp[e] {
+            mid
+        } else {
+            // the vertices opposite the edge in its two triangles
+            let opposite = edges.faces[e].iter().fold(Vector3::zeros(), |acc, &f| {
+                let t = &triangles[3 * f..3 * f + 3];
+                let o = t.iter().copied().find(|&v| v != a && v != b).unwrap();
+                acc + p(o)
+            });
+            mid * c(0.75) + opposite * c(0.125)
+        });
+    }
+
+    let mut out_triangles = Vec::with_capacity(triangles.len() * 4);
+    for t in triangles.chunks_exact(3) {
+        let m = |i: usize| first + edges.get(t[i], t[(i + 1) % 3]) as u32;
+        let (ab, bc, ca) = (m(0), m(1), m(2));
+        out_triangles.extend_from_slice(&[t[0], ab, ca, t[1], bc, ab, t[2], ca, bc, ab, bc, ca]);
+    }
+    (out, out_triangles, edges.split_creases(creases, first))
+}
+
+/// Apply one level of Catmull-Clark subdivision to a quad mesh.
+fn catmull_clark_step<F: Scalar>(
+    positions: &[Vector3<F>],
+    quads: &[u32],
+    creases: &HashSet<(u32, u32)>,
+) -> Subdivided<F> {
+    let edges = Edges::new(quads, 4, creases);
+    let p = |i: u32| positions[i as usize];
+    let c = |x: f64| na::convert::<f64, F>(x);
+
+    let face_points: Vec<Vector3<F>> = quads
+        .chunks_exact(4)
+        .map(|q| (p(q[0]) + p(q[1]) + p(q[2]) + p(q[3])) * c(0.25))
+        .collect();
+    let mut vertex_faces = vec![vec![]; positions.len()];
+    for (f, q) in quads.chunks_exact(4).enumerate() {
+        for &v in q {
+            vertex_faces[v as usize].push(f);
+        }
+    }
+
+    let mut out = edges.vertex_points(positions, |v, around| {
+        let n = around.len() as f64;
+        let faces = &vertex_faces[v];
+        let f = faces
+            .iter()
+            .fold(Vector3::zeros(), |acc, &f| acc + face_points[f])
+            / c(faces.len() as f64);
+        let r = around.iter().fold(Vector3::zeros(), |acc, &e| {
+            let (a, b) = edges.vertices[e];
+            acc + (p(a) + p(b)) * c(0.5)
+        }) / c(n);
+        (f + r * c(2.0) + positions[v] * c(n - 3.0)) / c(n)
+    });
+
+    let first_face = positions.len() as u32;
+    out.extend_from_slice(&face_points);
+    let first_edge = first_face + face_points.len() as u32;
+    for (e, &(a, b)) in edges.vertices.iter().enumerate() {
+        out.push(if edges.sharp[e] {
+            (p(a) + p(b)) * c(0.5)
+        } else {
+            let faces = &edges.faces[e];
+            (p(a) + p(b) + face_points[faces[0]] + face_points[faces[1]]) * c(0.25)
+        });
+    }
+
+    let mut out_quads = Vec::with_capacity(quads.len() * 4);
+    for (f, q) in quads.chunks_exact(4).enumerate() {
+        let m = |i: usize| first_edge + edges.get(q[i % 4], q[(i + 1) % 4]) as u32;
+        for (i, &v) in q.iter().enumerate() {
+            out_quads.extend_from_slice(&[v, m(i), first_face + f as u32, m(i + 3)]);
+        }
+    }
+    (out, out_quads, edges.split_creases(creases, first_edge))
+}
+
+/// Apply `levels` of Loop subdivision to a triangle mesh.
+///
+/// `creases` lists pairs of vertex indices joined by crease edges.
+/// Original vertices keep their indices, so creases can refer to the
+/// coarse mesh. Returns the new vertices and triangle indices.
+pub fn loop_subdivide<F: Scalar>(
+    positions: &[Vector3<F>],
+    triangles: &[u32],
+    creases: &[[u32; 2]],
+    levels: usize,
+) -> (Vec<Vector3<F>>, Vec<u32>) {
+    let mut creases = creases.iter().map(|e| edge_key(e[0], e[1])).collect();
+    let (mut positions, mut triangles) = (positions.to_vec(), triangles.to_vec());
+    for _ in 0..levels {
+        let (p, t, c) = loop_step(&positions, &triangles, &creases);
+        positions = p;
+        triangles = t;
+        creases = c;
+    }
+    (positions, triangles)
+}
+
+/// Apply `levels` of Catmull-Clark subdivision to a quad mesh, with
+/// four vertex indices per face.
+///
+/// `creases` lists pairs of vertex indices joined by crease edges.
+/// Original vertices keep their indices. Returns the new vertices and
+/// quad indices, which can be split with `quads_to_triangles`.
+pub fn catmull_clark<F: Scalar>(
+    positions: &[Vector3<F>],
+    quads: &[u32],
+    creases: &[[u32; 2]],
+    levels: usize,
+) -> (Vec<Vector3<F>>, Vec<u32>) {
+    let mut creases = creases.iter().map(|e| edge_key(e[0], e[1])).collect();
+    let (mut positions, mut quads) = (positions.to_vec(), quads.to_vec());
+    for _ in 0..levels {
+        let (p, q, c) = catmull_clark_step(&positions, &quads, &creases);
+        positions = p;
+        quads = q;
+        creases = c;
+    }
+    (positions, quads)
+}
+
+/// Split each quad (a, b, c, d) into the triangles (a, b, c) and
+/// (a, c, d).
+pub fn quads_to_triangles(quads: &[u32]) -> Vec<u32> {
+    quads
+        .chunks_exact(4)
+        .flat_map(|q| vec![q[0], q[1], q[2], q[0], q[2], q[3]])
+        .collect()
+}
+
+impl<F: Scalar> Mesh<F> {
+    /// Return the mesh after `levels` of Loop subdivision, with
+    /// creases between the given vertex pairs.
+    ///
+    /// Vertices with identical positions are merged first, so seams
+    /// from duplicated vertices (e.g. for UVs) are smoothed rather
+    /// than treated as boundaries. The result has smooth normals, but
+    /// no UVs.
+    pub fn subdivide_loop(&self, creases: &[[u32; 2]], levels: usize) -> Mesh<F> {
+        // number the first vertex at each position in order
+        let canonical = weld_positions(&self.positions);
+        let mut positions = vec![];
+        let mut welded = vec![0; self.positions.len()];
+        for (i, &c) in canonical.iter().enumerate() {
+            if c as usize == i {
+                welded[i] = positions.len() as u32;
+                positions.push(self.positions[i]);
+            } else {
+                welded[i] = welded[c as usize];
+            }
+        }
+        let indices: Vec<u32> = self.indices.iter().map(|&i| welded[i as usize]).collect();
+        let creases: Vec<[u32; 2]> = creases
+            .iter()
+            .map(|e| [welded[e[0] as usize], welded[e[1] as usize]])
+            .collect();
+
+        let (positions, indices) = loop_subdivide(&positions, &indices, &creases, levels);
+        let mut m = Mesh::new(positions, indices);
+        m.compute_normals();
+        m
+    }
+}
@@ -1,12 +1,22 @@
 use nalgebra::Vector3;
 use num::Integer;
-use std::convert::TryInto;
+use std::collections::HashSet;
+use std::convert::{TryFrom, TryInto};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Which lines of a grid of vertices to draw.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridLines {
+    /// Lines along each row, through increasing column index.
+    Rows,
+    /// Lines along each column.
+    Columns,
+    Both,
+}
 
 // extend an existing vertex and index list by a box
-pub fn add_box<
-    F: nalgebra::Scalar + nalgebra::RealField + num::Float,
-    I: Integer + std::convert::TryFrom<usize>,
->(
+pub fn add_box<F: nalgebra::RealField, I: Integer + std::convert::TryFrom<usize>>(
     v: &mut Vec<Vector3<F>>,
     indices: &mut Vec<I>,
     center: Vector3<F>,
@@ -22,9 +32,9 @@ pub fn add_box<
     for z in &[-1.0, 1.0] {
         for y in &[-1.0, 1.0] {
             for x in &[-1.0, 1.0] {
-                let fx: F = center.x + F::from(*x).unwrap() * half_widths.x;
-                let fy: F = center.y + F::from(*y).unwrap() * half_widths.y;
-                let fz: F = center.z + F::from(*z).unwrap() * half_widths.z;
+                let fx: F = center.x + nalgebra::convert::<f64, F>(*x) * half_widths.x;
+                let fy: F = center.y + nalgebra::convert::<f64, F>(*y) * half_widths.y;
+                let fz: F = center.z + nalgebra::convert::<f64, F>(*z) * half_widths.z;
 
                 v.push(Vector3::new(fx, fy, fz));
             }
@@ -42,8 +52,99 @@ pub fn add_box<
 /// starting at index `start` and rendering `n` points as consecutive lines.
 /// (start, start+1, start+1, start+2, ..., start+n-2, start+n-1.
 ///
-/// If `with_loop` is true, also append the indices from
+/// If `with_loop` is true, also append the indices from the last point
+/// back to the first, (start+n-1, start).
 pub fn add_linear_index(start: u32, n: u32, with_loop: bool) -> impl Iterator<Item = u32> {
     let last = 2 * if with_loop { n } else { n - 1 };
-    (0..last).map(move |i| start + (i + 1) / 2)
+    (0..last).map(move |i| start + ((i + 1) / 2) % n)
+}
+
+fn to_index<I: TryFrom<usize>>(i: usize) -> I
+where
+    <I as TryFrom<usize>>::Error: Debug,
+{
+    i.try_into().unwrap()
+}
+
+/// Extend a line index list by the unique edges of a triangle index
+/// list.
+///
+/// Each edge shared by several triangles is added once, in the
+/// direction it first appears, and edges already in `lines` are not
+/// repeated.
+pub fn add_triangle_edges<I: Integer + Copy + Hash>(lines: &mut Vec<I>, triangles: &[I]) {
+    let key = |a: I, b: I| if a < b { (a, b) } else { (b, a) };
+    let mut seen: HashSet<(I, I)> = lines.chunks_exact(2).map(|l| key(l[0], l[1])).collect();
+    for t in triangles.chunks_exact(3) {
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            if a != b && seen.insert(key(a, b)) {
+                lines.push(a);
+                lines.push(b);
+            }
+        }
+    }
+}
+
+/// Extend a line index list by the lines of a grid of `nx` by `ny`
+/// vertices, where vertex (i, j) is at index start + j * nx + i.
+///
+/// This matches the vertex layout of `Mesh::plane_grid` with nx + 1
+/// by nz + 1 vertices, or of a row-major heightfield.
+pub fn add_grid_lines<I: TryFrom<usize>>(
+    lines: &mut Vec<I>,
+    start: usize,
+    nx: usize,
+    ny: usize,
+    which: GridLines,
+) where
+    <I as TryFrom<usize>>::Error: Debug,
+{
+    let index = |i: usize, j: usize| to_index(start + j * nx + i);
+    if which != GridLines::Columns {
+        for j in 0..ny {
+            for i in 1..nx {
+                lines.push(index(i - 1, j));
+                lines.push(index(i, j));
+            }
+        }
+    }
+    if which != GridLines::Rows {
+        for i in 0..nx {
+            for j in 1..ny {
+                lines.push(index(i, j - 1));
+                lines.push(index(i, j));
+            }
+        }
+    }
+}
+
+/// Extend a line index list by several consecutive strips of
+/// vertices, starting at index `start`, with `lengths` giving the
+/// number of vertices in each strip.
+///
+/// If `with_loop` is true, each strip is closed back to its first
+/// vertex. Strips with fewer than two vertices add no lines.
+pub fn add_strips<I: TryFrom<usize>>(
+    lines: &mut Vec<I>,
+    start: usize,
+    lengths: &[usize],
+    with_loop: bool,
+) where
+    <I as TryFrom<usize>>::Error: Debug,
+{
+    let mut first = start;
+    for &n in lengths {
+        if n >= 2 {
+            for i in 1..n {
+                lines.push(to_index(first + i - 1));
+                lines.push(to_index(first + i));
+            }
+            if with_loop && n > 2 {
+                lines.push(to_index(first + n - 1));
+                lines.push(to_index(first));
+            }
+        }
+        first += n;
+    }
 }
@@ -0,0 +1,340 @@
+//! 2D optical ray tracing through scenes of simple elements.
+//!
+//! A scene is a collection of polygonal, circular or segment
+//! elements, each of which is either a dielectric (with a possibly
+//! wavelength-dependent refractive index), a mirror, or an
+//! absorber. Rays are traced through the scene, reflecting and
+//! refracting at each interface, and the resulting paths are
+//! returned as polylines.
+use crate::math::{
+    implicit_ray_intersect_2d, point_in_polygon_even_odd, reflect_dir, refract_dir, PointTest,
+    Refraction, Scalar,
+};
+use na::{Point2, Vector2};
+use nalgebra as na;
+
+/// Minimum distance along a ray for an intersection to count, to
+/// avoid re-hitting the surface a ray just left.
+fn hit_eps<F: Scalar>() -> F {
+    F::default_epsilon().sqrt()
+}
+
+/// Refractive index of a material, optionally as a function of
+/// wavelength.
+///
+/// Wavelengths are in micrometers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dispersion<F: Scalar> {
+    /// Wavelength-independent index.
+    Constant(F),
+
+    /// Cauchy's equation, n = a + b / λ² + c / λ⁴.
+    Cauchy { a: F, b: F, c: F },
+
+    /// Sellmeier equation, n² = 1 + Σ b_i λ² / (λ² - c_i).
+    Sellmeier { b: [F; 3], c: [F; 3] },
+}
+
+impl<F: Scalar> Dispersion<F> {
+    /// Return the refractive index at the wavelength (in micrometers).
+    pub fn index(&self, wavelength: F) -> F {
+        let l2 = wavelength * wavelength;
+        match self {
+            Self::Constant(n) => *n,
+            Self::Cauchy { a, b, c } => *a + *b / l2 + *c / (l2 * l2),
+            Self::Sellmeier { b, c } => {
+                let mut n2 = F::one();
+                for i in 0..3 {
+                    n2 += b[i] * l2 / (l2 - c[i]);
+                }
+                n2.sqrt()
+            }
+        }
+    }
+}
+
+/// How an element interacts with rays that hit it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Material<F: Scalar> {
+    /// Transparent material that refracts (and possibly reflects) rays.
+    Dielectric(Dispersion<F>),
+
+    /// Perfect reflector.
+    Mirror,
+
+    /// Terminates any ray that hits it.
+    Absorber,
+}
+
+/// Geometry of a scene element.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape<F: Scalar> {
+    /// Closed polygon, in either orientation.
+    Polygon(Vec<Point2<F>>),
+
+    /// Circle with a center and radius.
+    Circle { center: Point2<F>, radius: F },
+
+    /// Line segment. Segments have no interior, so a dielectric
+    /// segment has no effect on rays.
+    Segment(Point2<F>, Point2<F>),
+}
+
+impl<F: Scalar> Shape<F> {
+    /// Return the nearest intersection of the ray (`origin`, `dir`)
+    /// with the shape boundary past `hit_eps`, as the distance along
+    /// the ray and the (unnormalized) surface normal.
+    fn intersect(&self, origin: &Point2<F>, dir: &Vector2<F>) -> Option<(F, Vector2<F>)> {
+        match self {
+            Self::Polygon(points) => {
+                let n = points.len();
+                (0..n)
+                    .filter_map(|i| segment_hit(origin, dir, &points[i], &points[(i + 1) % n]))
+                    .min_by(|a, b| Into::<f64>::into(a.0).total_cmp(&b.0.into()))
+            }
+            Self::Circle { center, radius } => {
+                let oc = origin - center;
+                let b = dir.dot(&oc);
+                let disc = b * b - (oc.dot(&oc) - *radius * *radius);
+                if disc < F::zero() {
+                    return None;
+                }
+                let sq = disc.sqrt();
+                [-b - sq, -b + sq]
+                    .iter()
+                    .find(|t| **t > hit_eps())
+                    .map(|t| (*t, (origin + dir * *t) - center))
+            }
+            Self::Segment(a, b) => segment_hit(origin, dir, a, b),
+        }
+    }
+
+    /// Return true iff the point lies within the shape.
+    fn contains(&self, p: &Point2<F>) -> bool {
+        match self {
+            Self::Polygon(points) => point_in_polygon_even_odd(p, points) == PointTest::Inside,
+            Self::Circle { center, radius } => (p - center).norm_squared() < *radius * *radius,
+            Self::Segment(_, _) => false,
+        }
+    }
+}
+
+/// Intersect a ray with a line segment, returning the distance and
+/// segment normal.
+fn segment_hit<F: Scalar>(
+    origin: &Point2<F>,
+    dir: &Vector2<F>,
+    a: &Point2<F>,
+    b: &Point2<F>,
+) -> Option<(F, Vector2<F>)> {
+    let isect = implicit_ray_intersect_2d(*origin, origin + dir, *a, *b);
+    let (t, s) = (isect.t1()?, isect.t2()?);
+    if t > hit_eps() && s >= F::zero() && s <= F::one() {
+        let e = b - a;
+        Some((t, Vector2::new(-e.y, e.x)))
+    } else {
+        None
+    }
+}
+
+/// A single element of an optical scene.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element<F: Scalar> {
+    pub shape: Shape<F>,
+    pub material: Material<F>,
+}
+
+impl<F: Scalar> Element<F> {
+    pub fn new(shape: Shape<F>, material: Material<F>) -> Element<F> {
+        Element { shape, material }
+    }
+}
+
+/// A ray to be traced through a scene.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray2<F: Scalar> {
+    pub origin: Point2<F>,
+    pub dir: Vector2<F>,
+
+    /// Wavelength in micrometers.
+    pub wavelength: F,
+
+    /// Relative intensity of the ray, starting at 1.
+    pub intensity: F,
+}
+
+impl<F: Scalar> Ray2<F> {
+    /// Create a unit-intensity ray at a wavelength of 0.55μm.
+    pub fn new(origin: Point2<F>, dir: Vector2<F>) -> Ray2<F> {
+        Ray2 {
+            origin,
+            dir: dir.normalize(),
+            wavelength: na::convert(0.55),
+            intensity: F::one(),
+        }
+    }
+
+    /// Return the ray with a different wavelength, in micrometers.
+    pub fn with_wavelength(self, wavelength: F) -> Ray2<F> {
+        Ray2 { wavelength, ..self }
+    }
+}
+
+/// Parameters controlling ray tracing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TraceOptions<F: Scalar> {
+    /// Maximum number of interactions along any single path.
+    pub max_bounces: usize,
+
+    /// If true, split rays at dielectric interfaces into reflected
+    /// and refracted rays weighted by the Fresnel reflectance.
+    pub fresnel: bool,
+
+    /// Rays with intensity below this value are not traced.
+    pub min_intensity: F,
+
+    /// Length of the final segment of a ray that leaves the scene.
+    pub escape_length: F,
+}
+
+impl<F: Scalar> Default for TraceOptions<F> {
+    fn default() -> TraceOptions<F> {
+        TraceOptions {
+            max_bounces: 32,
+            fresnel: false,
+            min_intensity: na::convert(1e-3),
+            escape_length: na::convert(1000.0),
+        }
+    }
+}
+
+/// The path followed by a ray (or a branch of a split ray).
+#[derive(Clone, Debug, PartialEq)]
+pub struct RayPath<F: Scalar> {
+    pub points: Vec<Point2<F>>,
+
+    /// Intensity of the ray along the final segment of the path.
+    pub intensity: F,
+    pub wavelength: F,
+}
+
+/// A collection of optical elements within an ambient medium.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpticalScene<F: Scalar> {
+    pub elements: Vec<Element<F>>,
+    pub ambient: Dispersion<F>,
+}
+
+impl<F: Scalar> OpticalScene<F> {
+    /// Create an empty scene in a vacuum.
+    pub fn new() -> OpticalScene<F> {
+        OpticalScene {
+            elements: vec![],
+            ambient: Dispersion::Constant(F::one()),
+        }
+    }
+
+    pub fn add(&mut self, element: Element<F>) {
+        self.elements.push(element);
+    }
+
+    /// Return the refractive index of the medium at a point.
+    ///
+    /// When dielectrics overlap, the last one added takes precedence.
+    pub fn index_at(&self, p: &Point2<F>, wavelength: F) -> F {
+        self.elements
+            .iter()
+            .rev()
+            .find_map(|e| match e.material {
+                Material::Dielectric(d) if e.shape.contains(p) => Some(d.index(wavelength)),
+                _ => None,
+            })
+            .unwrap_or_else(|| self.ambient.index(wavelength))
+    }
+
+    /// Return the nearest element hit by a ray, as (distance, normal,
+    /// element index).
+    fn nearest_hit(&self, origin: &Point2<F>, dir: &Vector2<F>) -> Option<(F, Vector2<F>, usize)> {
+        self.elements
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| e.shape.intersect(origin, dir).map(|(t, n)| (t, n, i)))
+            .min_by(|a, b| Into::<f64>::into(a.0).total_cmp(&b.0.into()))
+    }
+
+    /// Trace a ray through the scene, returning the paths of the ray
+    /// and, if Fresnel splitting is enabled, any reflected branches.
+    ///
+    /// Branch paths start at the point where they split off from
+    /// their parent.
+    pub fn trace(&self, ray: &Ray2<F>, opts: &TraceOptions<F>) -> Vec<RayPath<F>> {
+        let mut paths = vec![];
+        let mut pending = vec![(*ray, 0)];
+
+        while let Some((mut ray, mut depth)) = pending.pop() {
+            let mut points = vec![ray.origin];
+            loop {
+                let (t, normal, ei) = match self.nearest_hit(&ray.origin, &ray.dir) {
+                    Some(hit) if depth < opts.max_bounces => hit,
+                    Some((t, _, _)) => {
+                        points.push(ray.origin + ray.dir * t);
+                        break;
+                    }
+                    None => {
+                        points.push(ray.origin + ray.dir * opts.escape_length);
+                        break;
+                    }
+                };
+                let p = ray.origin + ray.dir * t;
+                points.push(p);
+                depth += 1;
+
+                let new_dir = match self.elements[ei].material {
+                    Material::Absorber => break,
+                    Material::Mirror => reflect_dir(&ray.dir, &normal),
+                    Material::Dielectric(_) => {
+                        let eps = ray.dir * hit_eps::<F>();
+                        let n1 = self.index_at(&(p - eps), ray.wavelength);
+                        let n2 = self.index_at(&(p + eps), ray.wavelength);
+                        let refraction = refract_dir(ray.dir, normal, n1, n2);
+                        if let Refraction::Refracted { reflectance, .. } = refraction {
+                            if opts.fresnel {
+                                let reflected = Ray2 {
+                                    origin: p,
+                                    dir: reflect_dir(&ray.dir, &normal),
+                                    intensity: ray.intensity * reflectance,
+                                    ..ray
+                                };
+                                if reflected.intensity >= opts.min_intensity {
+                                    pending.push((reflected, depth));
+                                }
+                                ray.intensity *= F::one() - reflectance;
+                            }
+                        }
+                        refraction.dir()
+                    }
+                };
+
+                ray.origin = p;
+                ray.dir = new_dir.normalize();
+                if ray.intensity < opts.min_intensity {
+                    break;
+                }
+            }
+
+            paths.push(RayPath {
+                points,
+                intensity: ray.intensity,
+                wavelength: ray.wavelength,
+            });
+        }
+
+        paths
+    }
+}
+
+impl<F: Scalar> Default for OpticalScene<F> {
+    fn default() -> OpticalScene<F> {
+        OpticalScene::new()
+    }
+}
@@ -0,0 +1,272 @@
+//! Ordering and joining of polylines to reduce pen-up travel when
+//! plotting.
+use crate::math::Scalar;
+use crate::spatial_hash::SpatialHash2D;
+use na::Point2;
+use nalgebra as na;
+
+/// Options for `optimize_paths`.
+#[derive(Clone, Copy, Debug)]
+pub struct PlotOptimizeOptions<F: Scalar> {
+    /// Position of the pen before the first path.
+    pub origin: Point2<F>,
+
+    /// Polylines with endpoints within this distance of each other
+    /// are joined into one. `None` disables joining.
+    pub merge_tolerance: Option<F>,
+
+    /// Maximum number of 2-opt improvement passes over the ordering.
+    pub two_opt_passes: usize,
+
+    /// Number of nearby endpoints considered for each 2-opt move.
+    pub neighbors: usize,
+}
+
+impl<F: Scalar> Default for PlotOptimizeOptions<F> {
+    fn default() -> Self {
+        PlotOptimizeOptions {
+            origin: Point2::origin(),
+            merge_tolerance: Some(na::convert(1e-6)),
+            two_opt_passes: 8,
+            neighbors: 8,
+        }
+    }
+}
+
+/// Summary of an optimization.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlotStats<F: Scalar> {
+    /// Pen-up travel of the original paths, in order.
+    pub travel_before: F,
+    /// Pen-up travel of the optimized paths.
+    pub travel_after: F,
+    pub paths_before: usize,
+    pub paths_after: usize,
+}
+
+/// Return the total pen-up distance to draw the paths in order,
+/// starting from `origin`.
+pub fn pen_up_distance<F: Scalar>(paths: &[Vec<Point2<F>>], origin: &Point2<F>) -> F {
+    let mut pos = *origin;
+    let mut dist = F::zero();
+    for path in paths {
+        if let (Some(first), Some(last)) = (path.first(), path.last()) {
+            dist += (first - pos).norm();
+            pos = *last;
+        }
+    }
+    dist
+}
+
+/// Return a spatial hash of the path endpoints, where the start of
+/// path i has index 2i and the end has index 2i + 1.
+fn endpoint_hash<F: Scalar>(paths: &[Vec<Point2<F>>], origin: &Point2<F>) -> SpatialHash2D<F> {
+    let mut points = Vec::with_capacity(paths.len() * 2 + 1);
+    for path in paths {
+        points.push(path[0]);
+        points.push(*path.last().unwrap());
+    }
+    // include the origin in the range, but not as a point
+    points.push(*origin);
+    let mut hash = SpatialHash2D::bounding(&points);
+    hash.remove(points.len() - 1);
+    hash
+}
+
+/// Join paths whose endpoints lie within `tol` of each other.
+fn merge_paths<F: Scalar>(
+    paths: Vec<Vec<Point2<F>>>,
+    origin: &Point2<F>,
+    tol: F,
+) -> Vec<Vec<Point2<F>>> {
+    let mut hash = endpoint_hash(&paths, origin);
+    let mut used = vec![false; paths.len()];
+    let mut merged = vec![];
+
+    for i in 0..paths.len() {
+        if used[i] {
+            continue;
+        }
+        used[i] = true;
+        hash.remove(2 * i);
+        hash.remove(2 * i + 1);
+
+        // extend the end of the path, then the start
+        let mut path = paths[i].clone();
+        for _ in 0..2 {
+            while let Some(j) = hash.k_nearest(*path.last().unwrap(), 1).first().cloned() {
+                if (hash.point(j) - path.last().unwrap()).norm() > tol {
+                    break;
+                }
+                let q = j / 2;
+                used[q] = true;
+                hash.remove(2 * q);
+                hash.remove(2 * q + 1);
+
+                let next = &paths[q];
+                if j % 2 == 0 {
+                    path.extend(next.iter().skip(1));
+                } else {
+                    path.extend(next.iter().rev().skip(1));
+                }
+            }
+            path.reverse();
+        }
+        merged.push(path);
+    }
+    merged
+}
+
+/// A path in an ordering, possibly drawn in reverse.
+#[derive(Clone, Copy)]
+struct Step {
+    path: usize,
+    reversed: bool,
+}
+
+/// Order the paths greedily, always moving to the nearest endpoint of
+/// an undrawn path.
+fn order_nearest<F: Scalar>(paths: &[Vec<Point2<F>>], origin: &Point2<F>) -> Vec<Step> {
+    let mut hash = endpoint_hash(paths, origin);
+    let mut order = Vec::with_capacity(paths.len());
+    let mut pos = *origin;
+    while let Some(j) = hash.k_nearest(pos, 1).first().cloned() {
+        let step = Step {
+            path: j / 2,
+            reversed: j % 2 == 1,
+        };
+        hash.remove(2 * step.path);
+        hash.remove(2 * step.path + 1);
+        let path = &paths[step.path];
+        pos = if step.reversed {
+            path[0]
+        } else {
+            *path.last().unwrap()
+        };
+        order.push(step);
+    }
+    order
+}
+
+/// Improve an ordering with 2-opt moves, which reverse a run of
+/// consecutive steps (including the direction of each path).
+///
+/// Candidate moves for each position are taken from the nearest
+/// endpoints to the end of the previous path.
+fn two_opt<F: Scalar>(
+    paths: &[Vec<Point2<F>>],
+    order: &mut [Step],
+    origin: &Point2<F>,
+    passes: usize,
+    neighbors: usize,
+) {
+    let n = order.len();
+    let hash = endpoint_hash(paths, origin);
+    let mut pos = vec![0; paths.len()];
+    for (i, s) in order.iter().enumerate() {
+        pos[s.path] = i;
+    }
+
+    let start = |s: &Step| {
+        let p = &paths[s.path];
+        if s.reversed {
+            *p.last().unwrap()
+        } else {
+            p[0]
+        }
+    };
+    let end = |s: &Step| {
+        let p = &paths[s.path];
+        if s.reversed {
+            p[0]
+        } else {
+            *p.last().unwrap()
+        }
+    };
+    let dist = |a: Point2<F>, b: Point2<F>| (b - a).norm();
+
+    let scale = (0..hash.size()).fold(F::zero(), |m, i| {
+        let d = hash.point(i) - origin;
+        m.max(d.x.abs()).max(d.y.abs())
+    });
+    let min_gain = (scale + F::one()) * na::convert(1e-9);
+
+    for _ in 0..passes {
+        let mut improved = false;
+        for i in 0..n {
+            let a = if i == 0 { *origin } else { end(&order[i - 1]) };
+            for k in hash.k_nearest(a, neighbors) {
+                let j = pos[k / 2];
+                if j < i {
+                    continue;
+                }
+                let (si, ej) = (start(&order[i]), end(&order[j]));
+                let mut delta = dist(a, ej) - dist(a, si);
+                if j + 1 < n {
+                    let next = start(&order[j + 1]);
+                    delta += dist(si, next) - dist(ej, next);
+                }
+                if delta < -min_gain {
+                    order[i..=j].reverse();
+                    for (o, s) in order[i..=j].iter_mut().enumerate() {
+                        s.reversed = !s.reversed;
+                        pos[s.path] = i + o;
+                    }
+                    improved = true;
+                    break;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+}
+
+/// Reorder, reverse and join polylines to reduce the pen-up travel
+/// needed to plot them.
+///
+/// Paths are first joined where their endpoints coincide, then
+/// ordered greedily by nearest neighbor and improved with 2-opt
+/// moves. Empty paths are dropped.
+pub fn optimize_paths<F: Scalar>(
+    paths: &[Vec<Point2<F>>],
+    opts: &PlotOptimizeOptions<F>,
+) -> (Vec<Vec<Point2<F>>>, PlotStats<F>) {
+    let mut paths: Vec<Vec<Point2<F>>> = paths.iter().filter(|p| !p.is_empty()).cloned().collect();
+    let paths_before = paths.len();
+    let travel_before = pen_up_distance(&paths, &opts.origin);
+
+    if let Some(tol) = opts.merge_tolerance {
+        paths = merge_paths(paths, &opts.origin, tol);
+    }
+
+    let mut order = order_nearest(&paths, &opts.origin);
+    two_opt(
+        &paths,
+        &mut order,
+        &opts.origin,
+        opts.two_opt_passes,
+        opts.neighbors,
+    );
+
+    let mut paths: Vec<Option<Vec<Point2<F>>>> = paths.into_iter().map(Some).collect();
+    let optimized: Vec<Vec<Point2<F>>> = order
+        .iter()
+        .map(|s| {
+            let mut p = paths[s.path].take().unwrap();
+            if s.reversed {
+                p.reverse();
+            }
+            p
+        })
+        .collect();
+
+    let stats = PlotStats {
+        travel_before,
+        travel_after: pen_up_distance(&optimized, &opts.origin),
+        paths_before,
+        paths_after: optimized.len(),
+    };
+    (optimized, stats)
+}
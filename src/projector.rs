@@ -0,0 +1,83 @@
+//! Projection of 3D polylines into clipped 2D polylines on a page.
+use crate::camera::{ndc_to_rect, Camera};
+use crate::frustum::Frustum;
+use crate::math::{Rect, Scalar};
+use na::{Matrix4, Point2, Point3};
+use nalgebra as na;
+
+/// Clips world-space geometry to a view frustum and maps it onto a
+/// page rect.
+#[derive(Clone, Debug)]
+pub struct Projector<F: Scalar> {
+    clip: Matrix4<F>,
+    frustum: Frustum<F>,
+    rect: Rect<F>,
+}
+
+impl<F: Scalar> Projector<F> {
+    /// Create a projector from a world-to-clip matrix.
+    pub fn new(clip: &Matrix4<F>, rect: &Rect<F>) -> Projector<F> {
+        Projector {
+            clip: *clip,
+            frustum: Frustum::from_clip_matrix(clip),
+            rect: *rect,
+        }
+    }
+
+    pub fn from_camera(camera: &Camera<F>, rect: &Rect<F>) -> Projector<F> {
+        Self::new(&camera.clip_matrix(), rect)
+    }
+
+    pub fn frustum(&self) -> &Frustum<F> {
+        &self.frustum
+    }
+
+    pub fn clip_matrix(&self) -> &Matrix4<F> {
+        &self.clip
+    }
+
+    pub fn rect(&self) -> &Rect<F> {
+        &self.rect
+    }
+
+    /// Transform a point to normalized device coordinates, without
+    /// clipping.
+    pub fn to_ndc(&self, p: &Point3<F>) -> Point3<F> {
+        let c = self.clip * p.to_homogeneous();
+        Point3::new(c.x / c.w, c.y / c.w, c.z / c.w)
+    }
+
+    /// Project a point onto the page, without clipping.
+    ///
+    /// The point should be inside the frustum, or at least in front
+    /// of the eye.
+    pub fn project_point(&self, p: &Point3<F>) -> Point2<F> {
+        ndc_to_rect(&self.to_ndc(p).xy(), &self.rect)
+    }
+
+    /// Clip a 3D polyline to the frustum, returning the pieces inside
+    /// it in world coordinates.
+    ///
+    /// The polyline is split wherever it leaves the frustum, so a
+    /// polyline that leaves and re-enters gives multiple pieces.
+    pub fn clip_polyline(&self, points: &[Point3<F>]) -> Vec<Vec<Point3<F>>> {
+        self.frustum.clip_polyline(points)
+    }
+
+    /// Clip a 3D polyline to the frustum and project the pieces onto
+    /// the page, ready for `polyline_to_node`.
+    pub fn project_polyline(&self, points: &[Point3<F>]) -> Vec<Vec<Point2<F>>> {
+        self.clip_polyline(points)
+            .into_iter()
+            .map(|piece| piece.iter().map(|p| self.project_point(p)).collect())
+            .collect()
+    }
+
+    /// Clip and project each of the polylines.
+    pub fn project_polylines(&self, polylines: &[Vec<Point3<F>>]) -> Vec<Vec<Point2<F>>> {
+        polylines
+            .iter()
+            .flat_map(|pl| self.project_polyline(pl))
+            .collect()
+    }
+}
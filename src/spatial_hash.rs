@@ -1,34 +1,37 @@
 //! Quick and dirty 2D spatial hash for quick nearest-point and query selection
 
-use nalgebra::Scalar;
-use nalgebra::{Point2, SimdRealField};
+use crate::math::Scalar;
+use nalgebra as na;
+use nalgebra::Point2;
 use ndarray::prelude::*;
-use num::Float;
 use num_integer::Roots;
-use std::convert::{TryFrom, TryInto};
-use std::fmt::Debug;
 
 /// SpatialHash2D is a structure for enabling quick nearest-neighbor
-/// searches of a set of points. Points can be updated and inserted
+/// searches of a set of points. Points can be inserted and removed
 /// (though inserting many additional points may diminish the
 /// efficiency of the hash).
-pub struct SpatialHash2D<F: Scalar + Float + SimdRealField + TryFrom<u16> + Debug> {
+///
+/// Points outside of the range are stored in the border cells, so
+/// any point can be inserted.
+pub struct SpatialHash2D<F: Scalar> {
     min_range: Point2<F>,
     max_range: Point2<F>,
-    sn: F,
-    sn_i: u16,
+    sn: usize,
     points: Vec<Point2<F>>,
+    removed: Vec<bool>,
+    num_active: usize,
     grid_accel: Array2<Vec<usize>>,
 }
 
-impl<F: Scalar + Float + SimdRealField + TryFrom<u16> + Debug> SpatialHash2D<F> {
-    fn sindex(&self, p: &Point2<F>) -> (u16, u16) {
-        let rx = (p.x - self.min_range.x) / (self.max_range.x - self.min_range.x);
-        let ry = (p.y - self.min_range.y) / (self.max_range.y - self.min_range.y);
+impl<F: Scalar> SpatialHash2D<F> {
+    fn sindex(&self, p: &Point2<F>) -> (usize, usize) {
+        let rx: f64 = ((p.x - self.min_range.x) / (self.max_range.x - self.min_range.x)).into();
+        let ry: f64 = ((p.y - self.min_range.y) / (self.max_range.y - self.min_range.y)).into();
 
+        let max_i = (self.sn - 1) as f64;
         (
-            (rx * self.sn).to_u64().unwrap().try_into().unwrap(),
-            (ry * self.sn).to_u64().unwrap().try_into().unwrap(),
+            (rx * self.sn as f64).floor().max(0.0).min(max_i) as usize,
+            (ry * self.sn as f64).floor().max(0.0).min(max_i) as usize,
         )
     }
 
@@ -36,28 +39,59 @@ impl<F: Scalar + Float + SimdRealField + TryFrom<u16> + Debug> SpatialHash2D<F>
         self.min_range <= p && p < self.max_range
     }
 
-    pub fn insert(&mut self, p: Point2<F>) {
+    /// Insert a point, returning its index.
+    pub fn insert(&mut self, p: Point2<F>) -> usize {
         let n = self.points.len();
         self.points.push(p);
+        self.removed.push(false);
+        self.num_active += 1;
         let idx = self.sindex(&p);
-        self.grid_accel[(idx.0 as usize, idx.1 as usize)].push(n);
+        self.grid_accel[idx].push(n);
+        n
     }
 
+    /// Remove the point with index `i` from future queries. Indices
+    /// of the remaining points are unchanged.
+    ///
+    /// Returns false if the point was already removed.
+    pub fn remove(&mut self, i: usize) -> bool {
+        if self.removed[i] {
+            return false;
+        }
+        let idx = self.sindex(&self.points[i]);
+        self.grid_accel[idx].retain(|&j| j != i);
+        self.removed[i] = true;
+        self.num_active -= 1;
+        true
+    }
+
+    /// Return the point with index `i`.
+    pub fn point(&self, i: usize) -> Point2<F> {
+        self.points[i]
+    }
+
+    /// Return the number of points inserted, including removed points.
     pub fn size(&self) -> usize {
         self.points.len()
     }
 
+    /// Return the number of points that have not been removed.
+    pub fn active_size(&self) -> usize {
+        self.num_active
+    }
+
     /// Create a spatial hash structure with an intended capacity
     pub fn with_capacity(min_range: Point2<F>, max_range: Point2<F>, n: usize) -> SpatialHash2D<F> {
-        let sn = (n.sqrt() + 1) as u16;
-        let grid_accel = Array2::default((sn as usize, sn as usize));
+        let sn = n.sqrt() + 1;
+        let grid_accel = Array2::default((sn, sn));
 
         SpatialHash2D {
             min_range,
             max_range,
-            sn: sn.try_into().ok().unwrap(),
-            sn_i: sn,
-            points: vec![],
+            sn,
+            points: Vec::with_capacity(n),
+            removed: Vec::with_capacity(n),
+            num_active: 0,
             grid_accel,
         }
     }
@@ -77,49 +111,109 @@ impl<F: Scalar + Float + SimdRealField + TryFrom<u16> + Debug> SpatialHash2D<F>
         s_hash
     }
 
+    /// Create a spatial hash structure from an initial set of points,
+    /// with a range covering all of the points.
+    pub fn bounding(v: &[Point2<F>]) -> SpatialHash2D<F> {
+        let (lo, hi) = match v.first() {
+            Some(p) => v
+                .iter()
+                .fold((*p, *p), |(lo, hi), p| (lo.inf(p), hi.sup(p))),
+            None => (Point2::origin(), Point2::origin()),
+        };
+        // pad, so that the range is non-empty and the upper bound is
+        // exclusive.
+        let d = hi - lo;
+        let pad = d.x.max(d.y) * na::convert(1e-3) + F::one();
+        let pad = na::Vector2::new(pad, pad);
+        Self::from_points(lo - pad, hi + pad, v)
+    }
+
+    /// Return the index of the nearest point to `p`, or `None` if `p`
+    /// is out of range or there are no points.
     pub fn nearest_neighbor(&self, p: Point2<F>) -> Option<usize> {
         if !self.in_range(p) {
             return None;
         }
+        self.k_nearest(p, 1).first().cloned()
+    }
 
-        let idx = self.sindex(&p);
-        let mut best_p = None;
-        let mut best_dist_sq = None;
-
-        for w in 1..=self.sn_i {
-            let min_ix: usize = std::cmp::max(idx.0 as isize - w as isize, 0) as usize;
-            let max_ix: usize = std::cmp::min(idx.0 as usize + w as usize, self.sn_i as usize);
-            let min_iy: usize = std::cmp::max(idx.1 as isize - w as isize, 0) as usize;
-            let max_iy: usize = std::cmp::max(idx.0 as usize + w as usize, self.sn_i as usize);
-
-            for ix in min_ix..max_ix {
-                for iy in min_iy..max_iy {
-                    for pi in &self.grid_accel[[ix, iy]] {
-                        let d = self.points[*pi] - p;
-                        let d2 = d.dot(&d);
-                        if let Some(best_d2) = best_dist_sq {
-                            if best_d2 < d2 {
-                                continue;
-                            }
-                        }
-                        best_dist_sq = Some(d2);
-                        best_p = Some(*pi);
-                    }
+    /// Return the indices of the (up to) `k` nearest points to `p`,
+    /// ordered from nearest to farthest.
+    pub fn k_nearest(&self, p: Point2<F>, k: usize) -> Vec<usize> {
+        if k == 0 || self.num_active == 0 {
+            return vec![];
+        }
+
+        let (cx, cy) = self.sindex(&p);
+        let n = self.sn;
+        let cell = (self.max_range - self.min_range) / na::convert::<f64, F>(n as f64);
+        let mut found: Vec<(F, usize)> = vec![];
+
+        // search rings of cells of increasing radius w around the
+        // cell of p, until no unsearched cell can hold a closer point.
+        for w in 0..n {
+            let (x0, x1) = (cx as isize - w as isize, cx + w);
+            let (y0, y1) = (cy as isize - w as isize, cy + w);
+
+            let mut visit = |ix: isize, iy: isize| {
+                if ix < 0 || iy < 0 || ix as usize >= n || iy as usize >= n {
+                    return;
+                }
+                for &pi in &self.grid_accel[[ix as usize, iy as usize]] {
+                    found.push(((self.points[pi] - p).norm_squared(), pi));
                 }
+            };
+            for ix in x0..=x1 as isize {
+                visit(ix, y0);
+                if w > 0 {
+                    visit(ix, y1 as isize);
+                }
+            }
+            for iy in (y0 + 1)..y1 as isize {
+                visit(x0, iy);
+                visit(x1 as isize, iy);
             }
 
-            if best_p.is_some() {
-                return best_p;
+            // distance from p to the nearest side of the searched box
+            // beyond which there are unsearched cells
+            let mut bound: Option<F> = None;
+            let mut side = |exists: bool, d: F| {
+                if exists {
+                    bound = Some(bound.map_or(d, |b| b.min(d)));
+                }
+            };
+            let fw = |i: isize| -> F { na::convert(i as f64) };
+            side(x0 > 0, p.x - (self.min_range.x + cell.x * fw(x0)));
+            side(
+                x1 + 1 < n,
+                self.min_range.x + cell.x * fw(x1 as isize + 1) - p.x,
+            );
+            side(y0 > 0, p.y - (self.min_range.y + cell.y * fw(y0)));
+            side(
+                y1 + 1 < n,
+                self.min_range.y + cell.y * fw(y1 as isize + 1) - p.y,
+            );
+
+            if found.len() >= k {
+                found.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                found.truncate(k);
+            }
+            match bound {
+                None => break,
+                Some(b) if found.len() >= k && b >= F::zero() && b * b >= found[k - 1].0 => break,
+                _ => {}
             }
         }
 
-        panic!("Failed to find any point.");
+        found.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        found.into_iter().take(k).map(|(_, i)| i).collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::prelude::*;
 
     #[test]
     fn test_locate() {
@@ -134,4 +228,29 @@ mod tests {
         assert_eq!(shash.nearest_neighbor(Point2::new(0.0, 0.2)), Some(0));
         assert_eq!(shash.nearest_neighbor(Point2::new(-1.0, 0.0)), None);
     }
+
+    #[test]
+    fn test_k_nearest_remove() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let points: Vec<Point2<f64>> = (0..500)
+            .map(|_| Point2::new(rng.gen_range(0.0, 10.0), rng.gen_range(0.0, 3.0)))
+            .collect();
+        let mut shash = SpatialHash2D::bounding(&points);
+        for i in (0..500).step_by(3) {
+            assert!(shash.remove(i));
+        }
+        assert!(!shash.remove(0));
+        assert_eq!(shash.active_size(), 500 - 167);
+
+        for _ in 0..50 {
+            let q = Point2::new(rng.gen_range(-2.0, 12.0), rng.gen_range(-2.0, 5.0));
+            let mut brute: Vec<usize> = (0..500).filter(|i| i % 3 != 0).collect();
+            brute.sort_by(|&a, &b| {
+                let da = (points[a] - q).norm();
+                let db = (points[b] - q).norm();
+                da.partial_cmp(&db).unwrap()
+            });
+            assert_eq!(shash.k_nearest(q, 5), brute[..5].to_vec());
+        }
+    }
 }
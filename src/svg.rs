@@ -1,4 +1,5 @@
 //! SVG-related utilites
+use crate::math::{Scalar, Transform2};
 use itertools::Itertools;
 use nalgebra as na;
 use std::fmt::Display;
@@ -72,3 +73,8 @@ pub fn polygon_to_node<F: na::Scalar + Display>(
     }
     group
 }
+
+/// Set the `transform` attribute of a group to the transform.
+pub fn transform_group<F: Scalar + Display>(group: Group, t: &Transform2<F>) -> Group {
+    group.set("transform", t.to_svg_attribute())
+}
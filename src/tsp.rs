@@ -0,0 +1,368 @@
+//! Traveling salesman tours through point sets, for single-line
+//! ("TSP art") drawings.
+//!
+//! Tours are built by nearest-neighbor construction and improved
+//! with 2-opt and Or-opt moves, restricted to candidates from each
+//! point's nearest neighbors.
+use crate::math::Scalar;
+use crate::spatial_hash::SpatialHash2D;
+use na::Point2;
+use nalgebra as na;
+use num_integer::Roots;
+use std::collections::VecDeque;
+
+/// Options for `tsp_tour`.
+#[derive(Clone, Copy, Debug)]
+pub struct TspOptions {
+    /// Number of nearest neighbors considered as candidates for each
+    /// improving move.
+    pub neighbors: usize,
+
+    /// Try moving runs of up to three points elsewhere in the tour,
+    /// in addition to 2-opt moves.
+    pub or_opt: bool,
+
+    /// Remove all self-crossings from the final tour.
+    pub no_crossings: bool,
+}
+
+impl Default for TspOptions {
+    fn default() -> Self {
+        TspOptions {
+            neighbors: 10,
+            or_opt: true,
+            no_crossings: false,
+        }
+    }
+}
+
+/// Return the length of the closed tour visiting `points` in the
+/// order of `tour`.
+pub fn tour_length<F: Scalar>(points: &[Point2<F>], tour: &[usize]) -> F {
+    let n = tour.len();
+    (0..n).fold(F::zero(), |acc, i| {
+        acc + (points[tour[(i + 1) % n]] - points[tour[i]]).norm()
+    })
+}
+
+/// A closed tour, stored as an array of points with the inverse
+/// permutation.
+struct Tour {
+    order: Vec<usize>,
+    pos: Vec<usize>,
+}
+
+impl Tour {
+    fn new(order: Vec<usize>) -> Tour {
+        let mut pos = vec![0; order.len()];
+        for (i, &c) in order.iter().enumerate() {
+            pos[c] = i;
+        }
+        Tour { order, pos }
+    }
+
+    fn next(&self, c: usize) -> usize {
+        self.order[(self.pos[c] + 1) % self.order.len()]
+    }
+
+    fn prev(&self, c: usize) -> usize {
+        let n = self.order.len();
+        self.order[(self.pos[c] + n - 1) % n]
+    }
+
+    fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.next(a) == b || self.prev(a) == b
+    }
+
+    /// Reverse the run of the tour from `x` forward to `y`.
+    ///
+    /// The complementary run is reversed instead when it is shorter,
+    /// which gives the same cycle in the opposite direction.
+    fn reverse(&mut self, x: usize, y: usize) {
+        let n = self.order.len();
+        let (mut i, mut j) = (self.pos[x], self.pos[y]);
+        let mut len = (j + n - i) % n + 1;
+        if len * 2 > n {
+            let (ci, cj) = ((j + 1) % n, (i + n - 1) % n);
+            i = ci;
+            j = cj;
+            len = n - len;
+        }
+        for _ in 0..len / 2 {
+            self.order.swap(i, j);
+            self.pos[self.order[i]] = i;
+            self.pos[self.order[j]] = j;
+            i = (i + 1) % n;
+            j = (j + n - 1) % n;
+        }
+    }
+
+    /// Replace the edges (a, b) and (c, d) with (a, c) and (b, d),
+    /// where b follows a and d follows c in the same direction.
+    fn move_2opt(&mut self, a: usize, b: usize, c: usize, d: usize) {
+        if self.next(a) == b {
+            self.reverse(b, c);
+        } else {
+            self.reverse(a, d);
+        }
+    }
+}
+
+/// Build a tour by repeatedly moving to the nearest unvisited point.
+fn nearest_neighbor_tour<F: Scalar>(points: &[Point2<F>]) -> Vec<usize> {
+    let mut hash = SpatialHash2D::bounding(points);
+    let mut order = Vec::with_capacity(points.len());
+    let mut curr = 0;
+    hash.remove(curr);
+    order.push(curr);
+    while let Some(&next) = hash.k_nearest(points[curr], 1).first() {
+        hash.remove(next);
+        order.push(next);
+        curr = next;
+    }
+    order
+}
+
+struct Optimizer<'a, F: Scalar> {
+    points: &'a [Point2<F>],
+    neighbors: Vec<Vec<usize>>,
+    tour: Tour,
+    min_gain: F,
+}
+
+impl<'a, F: Scalar> Optimizer<'a, F> {
+    fn dist(&self, a: usize, b: usize) -> F {
+        (self.points[b] - self.points[a]).norm()
+    }
+
+    /// Try a 2-opt move replacing an edge at `a` with an edge to one
+    /// of its neighbors. Returns the points whose edges changed.
+    fn try_2opt(&mut self, a: usize) -> Option<[usize; 4]> {
+        for &forward in &[true, false] {
+            let step = |t: &Tour, c| if forward { t.next(c) } else { t.prev(c) };
+            let b = step(&self.tour, a);
+            let d_ab = self.dist(a, b);
+            for &c in &self.neighbors[a] {
+                let g1 = d_ab - self.dist(a, c);
+                if g1 <= self.min_gain {
+                    break;
+                }
+                let d = step(&self.tour, c);
+                if c == b || d == a {
+                    continue;
+                }
+                if g1 + self.dist(c, d) - self.dist(b, d) > self.min_gain {
+                    self.tour.move_2opt(a, b, c, d);
+                    return Some([a, b, c, d]);
+                }
+            }
+        }
+        None
+    }
+
+    /// Try moving a run of up to three points starting at `a` to
+    /// between a neighbor and its successor or predecessor, in either
+    /// direction. Returns the points whose edges changed.
+    fn try_or_opt(&mut self, a: usize) -> Option<[usize; 6]> {
+        let n = self.tour.order.len();
+        for len in 1..=3 {
+            if n < len + 3 {
+                break;
+            }
+            let s1 = a;
+            let mut seg = vec![s1];
+            for _ in 1..len {
+                seg.push(self.tour.next(*seg.last().unwrap()));
+            }
+            let s2 = *seg.last().unwrap();
+            let (p, nx) = (self.tour.prev(s1), self.tour.next(s2));
+            let removed = self.dist(p, s1) + self.dist(s2, nx) - self.dist(p, nx);
+            if removed <= self.min_gain {
+                continue;
+            }
+
+            let candidates = self.neighbors[s1].iter().chain(self.neighbors[s2].iter());
+            for &c in candidates {
+                if seg.contains(&c) {
+                    continue;
+                }
+                for &(e0, e1) in &[(c, self.tour.next(c)), (self.tour.prev(c), c)] {
+                    // moves next to the current position are covered
+                    // by 2-opt
+                    if seg.contains(&e0) || seg.contains(&e1) || e0 == nx || e1 == p {
+                        continue;
+                    }
+                    let base = self.dist(e0, e1);
+                    let add_fwd = self.dist(e0, s1) + self.dist(s2, e1) - base;
+                    let add_rev = self.dist(e0, s2) + self.dist(s1, e1) - base;
+                    let reversed = add_rev < add_fwd;
+                    if removed - add_fwd.min(add_rev) > self.min_gain {
+                        // p s1..s2 nx .. e0 e1 -> p nx .. e0 s2..s1 e1
+                        self.tour.move_2opt(p, s1, e0, e1);
+                        self.tour.move_2opt(p, e0, nx, s2);
+                        if !reversed {
+                            self.tour.move_2opt(e0, s2, s1, e1);
+                        }
+                        return Some([p, s1, s2, nx, e0, e1]);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Apply improving moves until none of the candidates improve
+    /// the tour.
+    fn optimize(&mut self, or_opt: bool) {
+        let n = self.points.len();
+        let mut queue: VecDeque<usize> = self.tour.order.iter().cloned().collect();
+        let mut queued = vec![true; n];
+
+        while let Some(a) = queue.pop_front() {
+            queued[a] = false;
+            let changed: Vec<usize> = if let Some(c) = self.try_2opt(a) {
+                c.to_vec()
+            } else if or_opt {
+                match self.try_or_opt(a) {
+                    Some(c) => c.to_vec(),
+                    None => continue,
+                }
+            } else {
+                continue;
+            };
+            for c in changed {
+                if !queued[c] {
+                    queued[c] = true;
+                    queue.push_back(c);
+                }
+            }
+        }
+    }
+
+    /// Return true iff the segments (a, b) and (c, d) cross at a
+    /// point interior to both.
+    fn crosses(&self, a: usize, b: usize, c: usize, d: usize) -> bool {
+        if a == c || a == d || b == c || b == d {
+            return false;
+        }
+        let p = self.points;
+        let orient = |o: usize, u: usize, v: usize| {
+            let z = (p[u].x - p[o].x) * (p[v].y - p[o].y) - (p[u].y - p[o].y) * (p[v].x - p[o].x);
+            if z > F::zero() {
+                1
+            } else if z < F::zero() {
+                -1
+            } else {
+                0
+            }
+        };
+        let (o1, o2) = (orient(a, b, c), orient(a, b, d));
+        let (o3, o4) = (orient(c, d, a), orient(c, d, b));
+        o1 * o2 < 0 && o3 * o4 < 0
+    }
+
+    /// Uncross edges until the tour has no self-crossings. Each
+    /// uncrossing is a 2-opt move that shortens the tour.
+    fn remove_crossings(&mut self) {
+        let n = self.points.len();
+        let lo = self.points.iter().fold(self.points[0], |a, b| a.inf(b));
+        let hi = self.points.iter().fold(self.points[0], |a, b| a.sup(b));
+        let g = n.sqrt().max(1);
+        let cell_of = |p: &Point2<F>| -> (usize, usize) {
+            let r = (p - lo).component_div(&(hi - lo));
+            let rx: f64 = r.x.into();
+            let ry: f64 = r.y.into();
+            let ix = (rx * g as f64).max(0.0).min((g - 1) as f64) as usize;
+            let iy = (ry * g as f64).max(0.0).min((g - 1) as f64) as usize;
+            (ix, iy)
+        };
+
+        loop {
+            // bin each edge into the grid cells its bounding box covers
+            let mut grid: Vec<Vec<(usize, usize)>> = vec![vec![]; g * g];
+            for &a in &self.tour.order {
+                let b = self.tour.next(a);
+                let (i0, j0) = cell_of(&self.points[a].inf(&self.points[b]));
+                let (i1, j1) = cell_of(&self.points[a].sup(&self.points[b]));
+                for i in i0..=i1 {
+                    for j in j0..=j1 {
+                        grid[i * g + j].push((a, b));
+                    }
+                }
+            }
+
+            let mut changed = false;
+            for cell in &grid {
+                for (k, &(a, b)) in cell.iter().enumerate() {
+                    for &(c, d) in &cell[k + 1..] {
+                        if !self.tour.has_edge(a, b)
+                            || !self.tour.has_edge(c, d)
+                            || !self.crosses(a, b, c, d)
+                        {
+                            continue;
+                        }
+                        let (a, b) = if self.tour.next(a) == b {
+                            (a, b)
+                        } else {
+                            (b, a)
+                        };
+                        let (c, d) = if self.tour.next(c) == d {
+                            (c, d)
+                        } else {
+                            (d, c)
+                        };
+                        self.tour.move_2opt(a, b, c, d);
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+}
+
+/// Return a short closed tour through all of the points, as a
+/// permutation of the point indices.
+///
+/// The tour starts at the first point. It is a local optimum of the
+/// candidate moves, not the shortest possible tour.
+pub fn tsp_tour<F: Scalar>(points: &[Point2<F>], opts: &TspOptions) -> Vec<usize> {
+    let n = points.len();
+    if n < 4 {
+        return (0..n).collect();
+    }
+
+    let hash = SpatialHash2D::bounding(points);
+    let neighbors = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let mut near = hash.k_nearest(*p, opts.neighbors + 1);
+            near.retain(|&j| j != i);
+            near.truncate(opts.neighbors);
+            near
+        })
+        .collect();
+
+    let lo = points.iter().fold(points[0], |a, b| a.inf(b));
+    let hi = points.iter().fold(points[0], |a, b| a.sup(b));
+    let eps: F = na::convert(64.0);
+    let mut opt = Optimizer {
+        points,
+        neighbors,
+        tour: Tour::new(nearest_neighbor_tour(points)),
+        min_gain: (hi - lo).norm() * F::default_epsilon() * eps,
+    };
+    opt.optimize(opts.or_opt);
+    if opts.no_crossings {
+        opt.remove_crossings();
+    }
+
+    // rotate so that the tour starts at the first point
+    let mut order = opt.tour.order;
+    let start = opt.tour.pos[0];
+    order.rotate_left(start);
+    order
+}
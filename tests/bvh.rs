@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod test {
+    use art_util::mesh::Bvh;
+    use art_util::{closest_point_on_triangle, ray_triangle_intersect, Mesh};
+    use assert_approx_eq::assert_approx_eq;
+    use na::{Point3, Vector3};
+    use nalgebra as na;
+    use rand::prelude::*;
+
+    /// A torus with a sphere in its hole, so rays can hit several
+    /// surfaces.
+    fn scene() -> Mesh<f64> {
+        let mut m = Mesh::torus(2.0, 0.5, 48, 24);
+        m.append(&Mesh::icosphere(1.0, 2));
+        m
+    }
+
+    fn random_point(rng: &mut StdRng, r: f64) -> Point3<f64> {
+        Point3::new(
+            rng.gen_range(-r, r),
+            rng.gen_range(-r, r),
+            rng.gen_range(-r, r),
+        )
+    }
+
+    /// The nearest hit, by testing every triangle.
+    fn brute_force_hit(m: &Mesh<f64>, o: &Point3<f64>, d: &Vector3<f64>) -> Option<(usize, f64)> {
+        (0..m.num_triangles())
+            .filter_map(|i| {
+                let [a, b, c] = m.triangle_positions(i);
+                ray_triangle_intersect(o, d, &a, &b, &c).map(|h| (i, h.t))
+            })
+            .min_by(|x, y| x.1.partial_cmp(&y.1).unwrap())
+    }
+
+    #[test]
+    fn test_closest_hit() {
+        let m = scene();
+        let bvh = m.bvh();
+        assert_eq!(bvh.num_triangles(), m.num_triangles());
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut hits = 0;
+        for _ in 0..200 {
+            let o = random_point(&mut rng, 4.0);
+            let d = random_point(&mut rng, 1.0) - Point3::origin();
+            let expected = brute_force_hit(&m, &o, &d);
+            let hit = bvh.closest_hit(&o, &d, 0.0, f64::INFINITY);
+            assert_eq!(hit.is_some(), expected.is_some());
+            if let (Some(hit), Some((_, t))) = (hit, expected) {
+                hits += 1;
+                assert_approx_eq!(hit.t, t);
+                assert!(bvh.any_hit(&o, &d, 0.0, f64::INFINITY).is_some());
+
+                // the hit is on its triangle
+                let [a, b, c] = bvh.triangle(hit.triangle);
+                let p = o + d * hit.t;
+                let q = a + (b - a) * hit.u + (c - a) * hit.v;
+                assert_approx_eq!((p - q).norm(), 0.0);
+
+                // and nothing is hit before it
+                assert!(bvh.any_hit(&o, &d, 0.0, hit.t * 0.999).is_none());
+            }
+        }
+        assert!(hits > 20);
+    }
+
+    #[test]
+    fn test_ray_limits() {
+        let bvh: Bvh<f64> = Mesh::cuboid(&Vector3::zeros(), &Vector3::new(1.0, 1.0, 1.0)).bvh();
+        let o = Point3::new(-3.0, 0.1, 0.2);
+        let d = Vector3::x();
+        assert_approx_eq!(bvh.closest_hit(&o, &d, 0.0, 10.0).unwrap().t, 2.0);
+
+        // skipping the near side hits the far side
+        assert_approx_eq!(bvh.closest_hit(&o, &d, 2.5, 10.0).unwrap().t, 4.0);
+        assert!(bvh.closest_hit(&o, &d, 0.0, 1.5).is_none());
+        assert!(bvh.any_hit(&o, &d, 4.5, 10.0).is_none());
+        assert!(bvh.closest_hit(&o, &-d, 0.0, 10.0).is_none());
+
+        let empty: Bvh<f64> = Mesh::default().bvh();
+        assert!(empty.closest_hit(&o, &d, 0.0, 10.0).is_none());
+        assert!(empty.nearest_point(&o).is_none());
+        assert!(empty.bounds().is_none());
+    }
+
+    #[test]
+    fn test_segment_occlusion() {
+        let bvh: Bvh<f64> = Mesh::icosphere(1.0, 2).bvh();
+        let eye = Point3::new(0.0, 0.0, 5.0);
+
+        // points on the near side are visible, on the far side hidden
+        let near = bvh
+            .closest_hit(&eye, &-Vector3::z(), 0.0, 10.0)
+            .map(|h| eye - Vector3::z() * h.t)
+            .unwrap();
+        assert!(!bvh.is_segment_occluded(&eye, &near, 1e-6));
+        assert!(bvh.is_segment_occluded(&eye, &Point3::new(0.0, 0.0, -1.0), 1e-6));
+        assert!(!bvh.is_segment_occluded(&eye, &Point3::new(2.0, 0.0, -1.0), 1e-6));
+    }
+
+    #[test]
+    fn test_nearest_point() {
+        let m = scene();
+        let bvh = m.bvh();
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..100 {
+            let p = random_point(&mut rng, 4.0);
+            let expected = (0..m.num_triangles())
+                .map(|i| {
+                    let [a, b, c] = m.triangle_positions(i);
+                    (closest_point_on_triangle(&p, &a, &b, &c) - p).norm()
+                })
+                .fold(f64::INFINITY, f64::min);
+            let nearest = bvh.nearest_point(&p).unwrap();
+            assert_approx_eq!(nearest.distance, expected);
+            assert_approx_eq!((nearest.point - p).norm(), expected);
+        }
+    }
+}
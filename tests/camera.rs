@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod test {
+    use art_util::camera::{Camera, Projection};
+    use art_util::Rect;
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::{Point2, Point3, Vector3};
+    use nalgebra_glm as glm;
+
+    fn camera() -> Camera<f64> {
+        Camera::perspective(
+            Point3::new(0.0, 0.0, 5.0),
+            Point3::origin(),
+            Vector3::y(),
+            std::f64::consts::FRAC_PI_2,
+            2.0,
+            0.1,
+            100.0,
+        )
+    }
+
+    #[test]
+    fn test_matrices() {
+        let cam = camera();
+        let expected = glm::perspective_rh(2.0, std::f64::consts::FRAC_PI_2, 0.1, 100.0)
+            * glm::look_at_rh(
+                &Vector3::new(0.0, 0.0, 5.0),
+                &Vector3::zeros(),
+                &Vector3::y(),
+            );
+        assert_approx_eq!((cam.clip_matrix() - expected).norm(), 0.0);
+
+        let frustum = cam.frustum();
+        assert!(frustum.is_point_in(&cam.target));
+        assert!(!frustum.is_point_in(&Point3::new(0.0, 0.0, 6.0)));
+    }
+
+    #[test]
+    fn test_project_to_rect() {
+        let page = Rect::from_points(&Point2::new(0.0, 0.0), &Point2::new(200.0, 100.0));
+        let cam = camera();
+
+        let c = cam.project_to_rect(&Point3::origin(), &page).unwrap();
+        assert_approx_eq!(c.x, 100.0);
+        assert_approx_eq!(c.y, 50.0);
+
+        // with a 90 degree fov, the top edge of the view is at y = 5
+        let top = cam
+            .project_to_rect(&Point3::new(0.0, 5.0, 0.0), &page)
+            .unwrap();
+        assert_approx_eq!(top.y, 0.0);
+        let right = cam
+            .project_to_rect(&Point3::new(10.0, 0.0, 0.0), &page)
+            .unwrap();
+        assert_approx_eq!(right.x, 200.0);
+
+        assert!(cam.project_ndc(&Point3::new(0.0, 0.0, 8.0)).is_none());
+
+        let mut ortho = Camera::orthographic(
+            Point3::new(0.0, 0.0, 5.0),
+            Point3::origin(),
+            Vector3::y(),
+            4.0,
+            1.0,
+            0.1,
+            100.0,
+        );
+        ortho.fit_aspect(&page);
+        assert_eq!(
+            ortho.projection,
+            Projection::Orthographic {
+                height: 4.0,
+                aspect: 2.0,
+                near: 0.1,
+                far: 100.0
+            }
+        );
+        let p = ortho
+            .project_to_rect(&Point3::new(4.0, -2.0, -20.0), &page)
+            .unwrap();
+        assert_approx_eq!(p.x, 200.0);
+        assert_approx_eq!(p.y, 100.0);
+    }
+
+    #[test]
+    fn test_orbit() {
+        let mut cam = camera();
+        for _ in 0..4 {
+            cam.orbit(std::f64::consts::FRAC_PI_2, 0.0);
+        }
+        assert_approx_eq!((cam.eye - Point3::new(0.0, 0.0, 5.0)).norm(), 0.0);
+
+        cam.orbit(std::f64::consts::FRAC_PI_2, 0.0);
+        assert_approx_eq!((cam.eye - Point3::new(5.0, 0.0, 0.0)).norm(), 0.0);
+
+        // pitching past the pole stops just short of it
+        cam.orbit(0.0, 3.0);
+        assert_approx_eq!((cam.eye - cam.target).norm(), 5.0);
+        assert!(cam.eye.y > 4.99 && cam.eye.x > 0.0);
+
+        cam.dolly(0.5);
+        assert_approx_eq!((cam.eye - cam.target).norm(), 2.5);
+
+        let mut cam = camera();
+        cam.pan(1.0, 2.0);
+        assert_approx_eq!((cam.target - Point3::new(1.0, 2.0, 0.0)).norm(), 0.0);
+        assert_approx_eq!((cam.eye - Point3::new(1.0, 2.0, 5.0)).norm(), 0.0);
+    }
+}
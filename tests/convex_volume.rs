@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod test {
+    use art_util::frustum::{ClipResult, ClipResultPartial};
+    use art_util::{Aabb, ConvexVolume, Frustum, Obb};
+    use assert_approx_eq::assert_approx_eq;
+    use na::{Isometry3, Point3, Vector3, Vector4};
+    use nalgebra as na;
+    use nalgebra_glm as glm;
+
+    fn unit_box() -> ConvexVolume<f64> {
+        ConvexVolume::from_aabb(&Aabb::from_points(
+            &Point3::new(-1.0, -1.0, -1.0),
+            &Point3::new(1.0, 1.0, 1.0),
+        ))
+    }
+
+    #[test]
+    fn test_point_in() {
+        let b = unit_box();
+        assert_eq!(b.planes.len(), 6);
+        assert!(b.is_point_in(&Point3::new(0.5, -0.5, 0.9)));
+        assert!(b.is_point_in(&Point3::new(1.0, 0.0, 0.0)));
+        assert!(!b.is_point_in(&Point3::new(1.1, 0.0, 0.0)));
+        assert!(b.is_point_in_or_on(&Point3::new(1.1, 0.0, 0.0), 0.2));
+
+        let frustum: Frustum<f64> =
+            Frustum::from_clip_matrix(&glm::ortho_rh(-1.0, 1.0, -1.0, 1.0, 0.0, 1.0));
+        let f = ConvexVolume::from_frustum(&frustum);
+        for p in &[Point3::new(0.0, 0.0, -0.5), Point3::new(0.0, 0.0, 0.5)] {
+            assert_eq!(f.is_point_in(p), frustum.is_point_in(p));
+        }
+
+        // a half-space given by an unnormalized plane
+        let h: ConvexVolume<f64> = ConvexVolume::from_planes(&[Vector4::new(0.0, 2.0, 0.0, -2.0)]);
+        assert_approx_eq!(h.planes[0].xyz().norm(), 1.0);
+        assert!(h.is_point_in(&Point3::new(5.0, 1.5, 5.0)));
+        assert!(!h.is_point_in(&Point3::new(5.0, 0.5, 5.0)));
+    }
+
+    #[test]
+    fn test_obb() {
+        let iso = Isometry3::new(
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::z() * std::f64::consts::FRAC_PI_4,
+        );
+        let obb = Obb::from_aabb(
+            &Aabb::from_points(&Point3::new(-1.0, -1.0, -1.0), &Point3::new(1.0, 1.0, 1.0)),
+            &iso,
+        );
+        let v = ConvexVolume::from_obb(&obb);
+        assert!(v.is_point_in(&Point3::new(2.4, 0.0, 0.0)));
+        assert!(!v.is_point_in(&Point3::new(2.0, 1.0, 0.0)));
+        for c in obb.corners().iter() {
+            assert!(v.is_point_in_or_on(c, 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        // cube corners plus interior points
+        let mut points = vec![];
+        for i in 0..8 {
+            let c = |b: usize| if i & b != 0 { 1.0 } else { -1.0 };
+            points.push(Point3::new(c(1), c(2), c(4)));
+        }
+        points.push(Point3::new(0.1, 0.2, 0.3));
+        points.push(Point3::new(-0.5, 0.5, 0.0));
+        let hull = ConvexVolume::convex_hull(&points).unwrap();
+        assert_eq!(hull.planes.len(), 6);
+        assert!(hull.is_point_in(&Point3::new(0.9, -0.9, 0.9)));
+        assert!(!hull.is_point_in(&Point3::new(1.1, 0.0, 0.0)));
+
+        // an octahedron
+        let octa: Vec<_> = (0..6)
+            .map(|i| {
+                let s = if i % 2 == 0 { 1.0 } else { -1.0 };
+                Point3::from(Vector3::ith(i / 2, s))
+            })
+            .collect();
+        let hull = ConvexVolume::convex_hull(&octa).unwrap();
+        assert_eq!(hull.planes.len(), 8);
+        assert!(hull.is_point_in(&Point3::new(0.3, 0.3, 0.3)));
+        assert!(!hull.is_point_in(&Point3::new(0.4, 0.4, 0.4)));
+
+        let flat = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+        ];
+        assert!(ConvexVolume::convex_hull(&flat).is_none());
+
+        // a run of collinear points along one edge of a tetrahedron
+        let mut points: Vec<_> = (0..5).map(|i| Point3::new(i as f64, 0.0, 0.0)).collect();
+        points.push(Point3::new(0.0, 1.0, 0.0));
+        points.push(Point3::new(0.0, 0.0, 1.0));
+        points.push(Point3::new(2.0, 0.0, 0.0));
+        let hull = ConvexVolume::convex_hull(&points).unwrap();
+        assert_eq!(hull.planes.len(), 4);
+        assert!(hull.is_point_in(&Point3::new(3.0, 0.1, 0.1)));
+        assert!(!hull.is_point_in(&Point3::new(4.1, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_clip() {
+        let b = unit_box();
+        match b.clip_line(&Point3::new(0.0, 0.0, 0.0), &Point3::new(3.0, 0.0, 0.0)) {
+            ClipResult::Partial(ClipResultPartial::Prefix, _, p) => assert_approx_eq!(p.x, 1.0),
+            r => panic!("unexpected {:?}", r),
+        }
+
+        let runs = b.clip_polyline(&[
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(2.0, 0.5, 0.0),
+            Point3::new(0.0, 0.5, 0.0),
+        ]);
+        assert_eq!(runs.len(), 2);
+
+        // a large square in the z = 0 plane is clipped to the box
+        let square = [
+            Point3::new(-3.0, -3.0, 0.0),
+            Point3::new(3.0, -3.0, 0.0),
+            Point3::new(3.0, 3.0, 0.0),
+            Point3::new(-3.0, 3.0, 0.0),
+        ];
+        let clipped = b.clip_polygon(&square);
+        assert_eq!(clipped.len(), 4);
+        for p in &clipped {
+            assert_approx_eq!(p.x.abs(), 1.0);
+            assert_approx_eq!(p.y.abs(), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = unit_box();
+        let b = ConvexVolume::from_aabb(&Aabb::from_points(
+            &Point3::new(0.0, 0.0, 0.0),
+            &Point3::new(2.0, 2.0, 2.0),
+        ));
+        let both = a.intersection(&b);
+        assert_eq!(both.planes.len(), 12);
+        assert!(both.is_point_in(&Point3::new(0.5, 0.5, 0.5)));
+        assert!(!both.is_point_in(&Point3::new(-0.5, 0.5, 0.5)));
+        assert!(!both.is_point_in(&Point3::new(1.5, 0.5, 0.5)));
+
+        let seg = both.clip_line(&Point3::new(-2.0, 0.5, 0.5), &Point3::new(2.0, 0.5, 0.5));
+        match seg {
+            ClipResult::Partial(ClipResultPartial::Infix, p0, p1) => {
+                assert_approx_eq!(p0.x, 0.0);
+                assert_approx_eq!(p1.x, 1.0);
+            }
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 #[cfg(test)]
 mod test {
-    use art_util::Frustum;
+    use art_util::frustum::{clip_line_homogeneous, ClipResult, ClipResultPartial};
+    use art_util::{Aabb, Containment, DepthSplit, Frustum, Obb};
     use assert_approx_eq::assert_approx_eq;
-    use na::{Vector3, Vector4};
+    use na::{Point3, Vector3, Vector4};
     use nalgebra as na;
     use nalgebra_glm as glm;
 
@@ -78,4 +79,307 @@ mod test {
             assert_approx_eq!(frustum.planes[i].normalize().dot(&target_planes[i]), 1.0);
         }
     }
+
+    #[test]
+    fn test_clip_polygon() {
+        let frustum: Frustum<f64> =
+            Frustum::from_clip_matrix(&glm::ortho_rh(-1.0, 1.0, -1.0, 1.0, 0.0, 1.0));
+
+        // a triangle covering the whole view is clipped to the square
+        let tri = [
+            Point3::new(-3.0, -3.0, -0.5),
+            Point3::new(6.0, -3.0, -0.5),
+            Point3::new(-3.0, 6.0, -0.5),
+        ];
+        let clipped = frustum.clip_polygon(&tri);
+        assert_eq!(clipped.len(), 4);
+        let area: f64 = (0..4)
+            .map(|i| {
+                let (a, b) = (clipped[i], clipped[(i + 1) % 4]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f64>()
+            * 0.5;
+        assert_approx_eq!(area, 4.0);
+
+        // behind the near plane
+        let behind: Vec<_> = tri.iter().map(|p| Point3::new(p.x, p.y, 0.5)).collect();
+        assert!(frustum.clip_polygon(&behind).is_empty());
+    }
+
+    #[test]
+    fn test_clip_triangles() {
+        let frustum: Frustum<f64> =
+            Frustum::from_clip_matrix(&glm::ortho_rh(-1.0, 1.0, -1.0, 1.0, 0.0, 1.0));
+        let vertices = vec![
+            Point3::new(0.0, 0.0, -0.5),
+            Point3::new(0.5, 0.0, -0.5),
+            Point3::new(0.0, 0.5, -0.5),
+            Point3::new(2.0, 0.0, -0.5),
+            Point3::new(5.0, 5.0, -0.5),
+        ];
+        let indices: Vec<u16> = vec![0, 1, 2, 1, 3, 2, 3, 4, 3];
+        let (v, idx) = frustum.clip_triangles(&vertices, &indices).unwrap();
+
+        // the first triangle is kept as-is, the second becomes a quad,
+        // and the last is outside.
+        assert_eq!(&idx[..3], &[0, 1, 2]);
+        assert_eq!(idx.len(), 9);
+        assert_eq!(v.len(), 7);
+        assert_eq!(&v[..5], &vertices[..]);
+        for i in &idx {
+            assert!(frustum.is_point_in_or_on(&v[*i as usize], 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_clip_triangles_index_limit() {
+        let frustum: Frustum<f64> =
+            Frustum::from_clip_matrix(&glm::ortho_rh(-1.0, 1.0, -1.0, 1.0, 0.0, 1.0));
+        let triangle = [
+            Point3::new(0.5, 0.0, -0.5),
+            Point3::new(2.0, 0.0, -0.5),
+            Point3::new(0.0, 0.5, -0.5),
+        ];
+
+        // clipping the triangle adds two vertices, which just fit in a
+        // u8 after 254 vertices, but not after 255
+        for &(n, fits) in &[(254usize, true), (255, false)] {
+            let mut vertices = vec![Point3::new(0.0, 0.0, -0.5); n - 3];
+            vertices.extend_from_slice(&triangle);
+            let indices: Vec<u8> = vec![n as u8 - 3, n as u8 - 2, n as u8 - 1];
+            let clipped = frustum.clip_triangles(&vertices, &indices);
+            assert_eq!(clipped.is_some(), fits);
+            if let Some((v, idx)) = clipped {
+                assert_eq!(v.len(), 256);
+                assert_eq!(idx.iter().max(), Some(&255));
+            }
+        }
+    }
+
+    #[test]
+    fn test_corners() {
+        let frustum: Frustum<f64> =
+            Frustum::from_clip_matrix(&glm::ortho_rh(-1.0, 1.0, -2.0, 2.0, 0.0, 1.0));
+        let c = frustum.corners();
+        assert_approx_eq!((c[0] - Point3::new(-1.0, -2.0, 0.0)).norm(), 0.0);
+        assert_approx_eq!((c[3] - Point3::new(1.0, 2.0, 0.0)).norm(), 0.0);
+        assert_approx_eq!((c[7] - Point3::new(1.0, 2.0, -1.0)).norm(), 0.0);
+
+        let frustum: Frustum<f64> = Frustum::from_clip_matrix(&glm::perspective_rh(
+            1.0,
+            std::f64::consts::FRAC_PI_2,
+            0.1,
+            10.0,
+        ));
+        let c = frustum.corners();
+        assert_approx_eq!((c[0] - Point3::new(-0.1, -0.1, -0.1)).norm(), 0.0);
+        assert_approx_eq!((c[7] - Point3::new(10.0, 10.0, -10.0)).norm(), 0.0, 1e-6);
+    }
+
+    #[test]
+    fn test_classify() {
+        let frustum: Frustum<f64> = Frustum::from_clip_matrix(&glm::perspective_rh(
+            1.0,
+            std::f64::consts::FRAC_PI_2,
+            0.1,
+            10.0,
+        ));
+
+        let c = Point3::new(0.0, 0.0, -5.0);
+        assert_eq!(frustum.classify_sphere(&c, 1.0), Containment::Inside);
+        assert_eq!(frustum.classify_sphere(&c, 4.0), Containment::Intersecting);
+        assert_eq!(
+            frustum.classify_sphere(&Point3::new(0.0, 0.0, 5.0), 1.0),
+            Containment::Outside
+        );
+
+        let b = Aabb::from_center_half_widths(&c, &Vector3::new(1.0, 1.0, 1.0));
+        assert_eq!(frustum.classify_aabb(&b), Containment::Inside);
+        let b = Aabb::from_center_half_widths(
+            &Point3::new(5.0, 0.0, -5.0),
+            &Vector3::new(1.0, 1.0, 1.0),
+        );
+        assert_eq!(frustum.classify_aabb(&b), Containment::Intersecting);
+        let b = Aabb::from_center_half_widths(
+            &Point3::new(8.0, 0.0, -5.0),
+            &Vector3::new(1.0, 1.0, 1.0),
+        );
+        assert_eq!(frustum.classify_aabb(&b), Containment::Outside);
+
+        // a thin box just outside the right plane, rotated to lie
+        // parallel to it
+        let iso = na::Isometry3::new(
+            Vector3::new(5.5, 0.0, -5.0),
+            Vector3::new(0.0, -std::f64::consts::FRAC_PI_4, 0.0),
+        );
+        let thin = Aabb::from_center_half_widths(&Point3::origin(), &Vector3::new(0.1, 1.0, 2.0));
+        let obb = Obb::from_aabb(&thin, &iso);
+        assert_eq!(frustum.classify_obb(&obb), Containment::Outside);
+        assert_eq!(
+            frustum.classify_aabb(&Aabb::bounding(&obb.corners()).unwrap()),
+            Containment::Intersecting
+        );
+    }
+
+    #[test]
+    fn test_split_depth() {
+        let frustum: Frustum<f64> = Frustum::from_clip_matrix(&glm::perspective_rh(
+            1.0,
+            std::f64::consts::FRAC_PI_2,
+            1.0,
+            100.0,
+        ));
+
+        let d = frustum.split_distances(2, DepthSplit::Logarithmic);
+        assert_approx_eq!(d[0], 1.0);
+        assert_approx_eq!(d[1], 10.0);
+        assert_approx_eq!(d[2], 100.0);
+        let d = frustum.split_distances(2, DepthSplit::Practical(0.5));
+        assert_approx_eq!(d[1], 0.5 * 10.0 + 0.5 * 50.5);
+        assert!(frustum.split_distances(0, DepthSplit::Uniform).is_empty());
+        assert!(frustum.split_depth(0, DepthSplit::Uniform).is_empty());
+
+        let slices = frustum.split_depth(4, DepthSplit::Uniform);
+        assert_eq!(slices.len(), 4);
+        for (i, z) in [2.0, 30.0, 60.0, 99.0].iter().enumerate() {
+            let p = Point3::new(0.5, -0.5, -z);
+            for (j, s) in slices.iter().enumerate() {
+                assert_eq!(s.is_point_in(&p), i == j);
+            }
+        }
+        let c = slices[1].corners();
+        assert_approx_eq!(c[0].z, -25.75);
+        assert_approx_eq!(c[0].x, -25.75);
+        assert_approx_eq!(c[7].z, -50.5);
+
+        // orthographic frustums split uniformly
+        let ortho: Frustum<f64> =
+            Frustum::from_clip_matrix(&glm::ortho_rh(-1.0, 1.0, -1.0, 1.0, 1.0, 5.0));
+        let d = ortho.split_distances(2, DepthSplit::Logarithmic);
+        assert_approx_eq!(d[1] - d[0], 2.0);
+        let slices = ortho.split_depth(2, DepthSplit::Logarithmic);
+        assert!(slices[0].is_point_in(&Point3::new(0.0, 0.0, -2.9)));
+        assert!(slices[1].is_point_in(&Point3::new(0.0, 0.0, -3.1)));
+    }
+
+    #[test]
+    fn test_split_tiles() {
+        let view = glm::look_at_rh(
+            &Vector3::new(1.0, 2.0, 3.0),
+            &Vector3::new(0.0, 0.0, 0.0),
+            &Vector3::new(0.0, 1.0, 0.0),
+        );
+        let clip = glm::perspective_rh(1.5, 1.0, 0.1, 10.0) * view;
+        let frustum: Frustum<f64> = Frustum::from_clip_matrix(&clip);
+        let tiles = frustum.split_tiles(3, 2);
+        assert_eq!(tiles.len(), 6);
+
+        // points land in the tile matching their screen position
+        for &(x, y) in &[(-0.9, -0.9), (0.1, -0.5), (0.9, 0.2), (-0.5, 0.7)] {
+            let ndc = glm::vec4(x, y, 0.5, 1.0);
+            let p = clip.try_inverse().unwrap() * ndc;
+            let p = Point3::new(p.x / p.w, p.y / p.w, p.z / p.w);
+            let i = ((x + 1.0) * 1.5) as usize;
+            let j = ((y + 1.0) * 1.0) as usize;
+            for (k, t) in tiles.iter().enumerate() {
+                assert_eq!(t.is_point_in(&p), k == j * 3 + i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_clip_line() {
+        let frustum: Frustum<f64> =
+            Frustum::from_clip_matrix(&glm::ortho_rh(-1.0, 1.0, -1.0, 1.0, 0.0, 1.0));
+
+        let a = Point3::new(-0.5, 0.0, -0.5);
+        let b = Point3::new(0.5, 0.0, -0.5);
+        assert_eq!(frustum.clip_line(&a, &b), ClipResult::Inside(a, b));
+
+        match frustum.clip_line(&a, &Point3::new(1.5, 0.0, -0.5)) {
+            ClipResult::Partial(ClipResultPartial::Prefix, p0, p1) => {
+                assert_eq!(p0, a);
+                assert_approx_eq!(p1.x, 1.0);
+            }
+            r => panic!("unexpected {:?}", r),
+        }
+        match frustum.clip_line(&Point3::new(-2.0, 0.0, -0.5), &Point3::new(2.0, 0.0, -0.5)) {
+            ClipResult::Partial(ClipResultPartial::Infix, p0, p1) => {
+                assert_approx_eq!(p0.x, -1.0);
+                assert_approx_eq!(p1.x, 1.0);
+            }
+            r => panic!("unexpected {:?}", r),
+        }
+
+        // endpoints exactly on a plane count as inside
+        let on = Point3::new(1.0, 0.0, -0.5);
+        assert_eq!(frustum.clip_line(&a, &on), ClipResult::Inside(a, on));
+
+        // through a corner of the frustum, and touching an edge from
+        // outside
+        let corner = frustum.clip_line(&Point3::new(0.0, 2.0, -0.5), &Point3::new(2.0, 0.0, -0.5));
+        assert_eq!(corner, ClipResult::Outside);
+        let edge = frustum.clip_line(&Point3::new(1.0, 1.0, 0.5), &Point3::new(1.0, 1.0, -1.5));
+        assert!(matches!(
+            edge,
+            ClipResult::Partial(ClipResultPartial::Infix, _, _)
+        ));
+        let parallel = frustum.clip_line(&Point3::new(2.0, 0.0, 0.5), &Point3::new(2.0, 0.0, -1.5));
+        assert_eq!(parallel, ClipResult::Outside);
+    }
+
+    #[test]
+    fn test_clip_polyline() {
+        let frustum: Frustum<f64> =
+            Frustum::from_clip_matrix(&glm::ortho_rh(-1.0, 1.0, -1.0, 1.0, 0.0, 1.0));
+
+        // leaves through the right side and re-enters
+        let points = [
+            Point3::new(0.0, 0.0, -0.5),
+            Point3::new(0.5, 0.0, -0.5),
+            Point3::new(2.0, 0.0, -0.5),
+            Point3::new(2.0, 0.5, -0.5),
+            Point3::new(0.0, 0.5, -0.5),
+            Point3::new(0.0, 0.8, -0.5),
+        ];
+        let runs = frustum.clip_polyline(&points);
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].len(), 3);
+        assert_approx_eq!(runs[0][2].x, 1.0);
+        assert_eq!(runs[1].len(), 3);
+        assert_approx_eq!(runs[1][0].x, 1.0);
+        assert_approx_eq!(runs[1][0].y, 0.5);
+        assert_eq!(runs[1][2], points[5]);
+
+        assert!(frustum
+            .clip_polyline(&[Point3::new(2.0, 0.0, -0.5), Point3::new(3.0, 0.0, -0.5)])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_clip_line_homogeneous() {
+        let clip = glm::perspective_rh(1.0, std::f64::consts::FRAC_PI_2, 0.1, 10.0);
+        let frustum = Frustum::from_clip_matrix(&clip);
+
+        // a segment passing behind the eye
+        let a = Point3::new(-1.0, 0.2, -3.0);
+        let b = Point3::new(2.0, -0.1, 4.0);
+        let (c0, c1) =
+            clip_line_homogeneous(&(clip * a.to_homogeneous()), &(clip * b.to_homogeneous()))
+                .unwrap();
+        let (p0, p1) = match frustum.clip_line(&a, &b) {
+            ClipResult::Partial(_, p0, p1) => (p0, p1),
+            r => panic!("unexpected {:?}", r),
+        };
+        for (c, p) in [(c0, p0), (c1, p1)].iter() {
+            let q = clip * p.to_homogeneous();
+            for i in 0..3 {
+                assert_approx_eq!(c[i] / c.w, q[i] / q.w);
+            }
+        }
+
+        let c = clip * Point3::new(0.0, 0.0, 1.0).to_homogeneous();
+        assert!(clip_line_homogeneous(&c, &(c * 2.0)).is_none());
+    }
 }
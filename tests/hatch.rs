@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod test {
+    use art_util::hatch::{contour_hatch, cross_hatch, density_hatch, hatch, HatchLayer};
+    use art_util::{point_in_polygon_even_odd, PointTest};
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::Point2;
+
+    fn square(c: f64, r: f64) -> Vec<Point2<f64>> {
+        vec![
+            Point2::new(c - r, c - r),
+            Point2::new(c + r, c - r),
+            Point2::new(c + r, c + r),
+            Point2::new(c - r, c + r),
+        ]
+    }
+
+    #[test]
+    fn test_hatch_with_hole() {
+        let rings = vec![square(5.25, 5.0), square(5.25, 2.0)];
+        let lines = hatch(&rings, &HatchLayer::new(0.0, 1.0));
+
+        // lines at y = 1..=10, split in two where they cross the hole
+        // at y = 4..=7
+        assert_eq!(lines.len(), 10 + 4);
+        let total: f64 = lines.iter().map(|l| (l[1] - l[0]).norm()).sum();
+        assert_approx_eq!(total, 10.0 * 10.0 - 4.0 * 4.0);
+
+        for l in &lines {
+            let mid = nalgebra::center(&l[0], &l[1]);
+            assert_ne!(
+                point_in_polygon_even_odd(&mid, &rings[1]),
+                PointTest::Inside
+            );
+        }
+
+        let crossed = cross_hatch(&rings, &HatchLayer::new(0.3, 1.0), 2);
+        assert!(crossed.len() > lines.len());
+    }
+
+    #[test]
+    fn test_density_hatch() {
+        let rings = vec![square(5.0, 5.0)];
+        let layer = HatchLayer::new(0.0, 0.5);
+        let full = density_hatch(&rings, &layer, 0.1, |_| 1.0);
+        assert_eq!(full.len(), hatch(&rings, &layer).len());
+        assert!(density_hatch(&rings, &layer, 0.1, |_| 0.0).is_empty());
+
+        // half density on the right side only
+        let half = density_hatch(&rings, &layer, 0.1, |p| if p.x > 5.0 { 0.5 } else { 1.0 });
+        let right: f64 = half.iter().map(|l| l[1].x.max(5.0) - l[0].x.max(5.0)).sum();
+        let left: f64 = half.iter().map(|l| l[1].x.min(5.0) - l[0].x.min(5.0)).sum();
+        assert!((right / left - 0.5).abs() < 0.15);
+    }
+
+    #[test]
+    fn test_contour_hatch() {
+        let rings = vec![square(5.0, 5.0)];
+        let loops = contour_hatch(&rings, 1.0, 0.1);
+        assert_eq!(loops.len(), 4);
+        for l in &loops {
+            assert_eq!(l.first(), l.last());
+            for p in l {
+                assert_eq!(point_in_polygon_even_odd(p, &rings[0]), PointTest::Inside);
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_positive_spacing() {
+        let rings = vec![square(5.0, 5.0)];
+        for spacing in [0.0, -1.0].iter() {
+            let layer = HatchLayer::new(0.0, *spacing);
+            assert!(hatch(&rings, &layer).is_empty());
+            assert!(density_hatch(&rings, &layer, 0.1, |_| 1.0).is_empty());
+            assert!(contour_hatch(&rings, *spacing, 0.1).is_empty());
+        }
+
+        let layer = HatchLayer::new(0.0, 0.5);
+        for x in [0.0, -0.1].iter() {
+            assert!(density_hatch(&rings, &layer, *x, |_| 1.0).is_empty());
+            assert!(contour_hatch(&rings, 1.0, *x).is_empty());
+        }
+    }
+}
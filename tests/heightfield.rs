@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod test {
+    use art_util::heightfield::{heightfield_mesh, heightfield_scanlines, ridge_lines};
+    use art_util::Rect;
+    use assert_approx_eq::assert_approx_eq;
+    use na::{Point2, Vector2};
+    use nalgebra as na;
+    use ndarray::{arr2, Array2};
+
+    #[test]
+    fn test_mesh() {
+        let h = arr2(&[[0.0f64, 1.0, 0.0], [0.0, 2.0, 0.0]]);
+        let m = heightfield_mesh(&h, &Vector2::new(2.0, 1.0), 0.5);
+        assert_eq!(m.num_vertices(), 6);
+        assert_eq!(m.num_triangles(), 4);
+
+        // vertex (i, j) is at index j * ncols + i
+        assert_approx_eq!(m.positions[4].y, 1.0);
+        assert_approx_eq!(m.positions[4].x, 0.0);
+        assert_approx_eq!(m.positions[5].x, 1.0);
+        assert_approx_eq!(m.positions[5].z, 0.5);
+        for n in m.normals.as_ref().unwrap() {
+            assert!(n.y > 0.0);
+        }
+
+        let lines = heightfield_scanlines(&h, &Vector2::new(2.0, 1.0), 0.5);
+        assert_eq!(lines.len(), 2);
+        for (j, line) in lines.iter().enumerate() {
+            for (i, p) in line.iter().enumerate() {
+                assert_approx_eq!((p.coords - m.positions[j * 3 + i]).norm(), 0.0);
+            }
+        }
+
+        // too small for a surface
+        for dim in [(0, 0), (1, 3), (3, 1)].iter() {
+            let m = heightfield_mesh(&Array2::<f64>::zeros(*dim), &Vector2::new(2.0, 1.0), 0.5);
+            assert_eq!(m.num_vertices(), 0);
+            assert_eq!(m.num_triangles(), 0);
+        }
+    }
+
+    #[test]
+    fn test_ridge_lines_flat() {
+        let h: Array2<f64> = Array2::zeros((3, 4));
+        let rect = Rect::from_points(&Point2::new(0.0f64, 0.0), &Point2::new(3.0, 2.0));
+        let lines = ridge_lines(&h, &rect, 1.0);
+
+        // flat rows are all visible, from the bottom up
+        assert_eq!(lines.len(), 3);
+        for (j, line) in lines.iter().enumerate() {
+            assert_eq!(line.len(), 4);
+            for p in line {
+                assert_approx_eq!(p.y, 2.0 - j as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ridge_lines_occlusion() {
+        // a tall peak in the front row hides the middle of the back row
+        let h = arr2(&[[0.0f64, 0.0, 4.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0, 0.0]]);
+        let rect = Rect::from_points(&Point2::new(0.0f64, 0.0), &Point2::new(4.0, 1.0));
+        let lines = ridge_lines(&h, &rect, 1.0);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].len(), 5);
+
+        // the front peak rises from y = 1 to y = -3 between x = 1 and
+        // 2, crossing the back row at y = 0 a quarter of the way up
+        let (left, right) = (&lines[1], &lines[2]);
+        assert_approx_eq!(left[0].x, 0.0);
+        assert_approx_eq!(left.last().unwrap().x, 1.25);
+        assert_approx_eq!(right[0].x, 2.75);
+        assert_approx_eq!(right.last().unwrap().x, 4.0);
+        for p in left.iter().chain(right.iter()) {
+            assert_approx_eq!(p.y, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_ridge_lines_hidden_row() {
+        // a back row entirely below the front row is hidden
+        let h = arr2(&[[2.0f64, 2.0, 2.0], [0.0, 0.5, 0.0]]);
+        let rect = Rect::from_points(&Point2::new(0.0f64, 0.0), &Point2::new(2.0, 1.0));
+        let lines = ridge_lines(&h, &rect, 1.0);
+        assert_eq!(lines.len(), 1);
+
+        // and partly visible when it rises above
+        let h = arr2(&[[1.5f64, 1.5, 1.5], [0.0, 2.0, 0.0]]);
+        let lines = ridge_lines(&h, &rect, 1.0);
+        assert_eq!(lines.len(), 2);
+        assert_approx_eq!(lines[1][0].x, 0.25);
+        assert_approx_eq!(lines[1][1].x, 1.0);
+        assert_approx_eq!(lines[1][2].x, 1.75);
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod test {
+    use art_util::hidden_line::{visible_segments, HiddenLineOptions};
+    use art_util::{add_box, Camera, Projector, Rect};
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::{Point2, Point3, Vector3};
+
+    fn projector() -> Projector<f64> {
+        let page = Rect::from_points(&Point2::new(0.0, 0.0), &Point2::new(100.0, 100.0));
+        let camera = Camera::perspective(
+            Point3::new(0.0, 0.0, 5.0),
+            Point3::origin(),
+            Vector3::y(),
+            std::f64::consts::FRAC_PI_2,
+            1.0,
+            0.1,
+            100.0,
+        );
+        Projector::from_camera(&camera, &page)
+    }
+
+    fn total_length(segments: &[Vec<Point2<f64>>]) -> f64 {
+        segments.iter().map(|s| (s[1] - s[0]).norm()).sum()
+    }
+
+    #[test]
+    fn test_box_edges() {
+        let proj = projector();
+        let mut vertices = vec![];
+        let mut triangles: Vec<u32> = vec![];
+        add_box(
+            &mut vertices,
+            &mut triangles,
+            Vector3::zeros(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        // the front face (z = 1) is at 4-7, the back face at 0-3
+        let front: Vec<u32> = vec![4, 5, 5, 7, 7, 6, 6, 4];
+        let back: Vec<u32> = vec![0, 1, 1, 3, 3, 2, 2, 0];
+        let opts = HiddenLineOptions::default();
+
+        let visible = visible_segments(&proj, &vertices, &triangles, &front, &opts);
+        assert_eq!(visible.len(), 4);
+        let expected: f64 = front
+            .chunks(2)
+            .map(|l| {
+                let a = proj.project_point(&Point3::from(vertices[l[0] as usize]));
+                let b = proj.project_point(&Point3::from(vertices[l[1] as usize]));
+                (b - a).norm()
+            })
+            .sum();
+        assert_approx_eq!(total_length(&visible), expected);
+
+        assert!(visible_segments(&proj, &vertices, &triangles, &back, &opts).is_empty());
+    }
+
+    #[test]
+    fn test_partial_occlusion() {
+        let proj = projector();
+        // a square in the z = 0 plane, and segments behind and through it
+        let vertices = vec![
+            Vector3::new(-1.0, -1.0, 0.0),
+            Vector3::new(1.0, -1.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+            Vector3::new(-1.0, 1.0, 0.0),
+            Vector3::new(-2.0, 0.0, -1.0),
+            Vector3::new(2.0, 0.0, -1.0),
+            Vector3::new(0.0, -0.5, -1.0),
+            Vector3::new(0.0, 0.5, 1.0),
+        ];
+        let triangles: Vec<usize> = vec![0, 1, 2, 0, 2, 3];
+
+        // behind the square: at z = -1 the square covers |x| < 1.2
+        let behind = visible_segments(
+            &proj,
+            &vertices,
+            &triangles,
+            &[4, 5],
+            &HiddenLineOptions::default(),
+        );
+        assert_eq!(behind.len(), 2);
+        let a = proj.project_point(&Point3::new(-1.2, 0.0, -1.0));
+        assert_approx_eq!(behind[0][1].x, a.x);
+        let b = proj.project_point(&Point3::new(1.2, 0.0, -1.0));
+        assert_approx_eq!(behind[1][0].x, b.x);
+
+        // piercing the square at its center: the back half is hidden
+        let pierce = visible_segments(
+            &proj,
+            &vertices,
+            &triangles,
+            &[6, 7],
+            &HiddenLineOptions::default(),
+        );
+        assert_eq!(pierce.len(), 1);
+        // up to the depth bias
+        let c = proj.project_point(&Point3::new(0.0, 0.0, 0.0));
+        assert_approx_eq!(pierce[0][0].y, c.y, 1e-2);
+        assert_approx_eq!(pierce[0][0].x, c.x, 1e-2);
+    }
+}
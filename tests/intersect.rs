@@ -1,8 +1,12 @@
 #[cfg(test)]
 mod test {
+    use art_util::{
+        closest_point_on_triangle, closest_points_segments, point_segment_distance,
+        ray_aabb_intersect, ray_plane_intersect, ray_triangle_intersect, Aabb,
+    };
     use art_util::{implicit_ray_intersect_2d, line_intersect_2d};
     use assert_approx_eq::assert_approx_eq;
-    use nalgebra::Point2;
+    use nalgebra::{Point2, Point3, Vector3, Vector4};
 
     #[test]
     fn test_line_isect() {
@@ -32,4 +36,101 @@ mod test {
         assert_approx_eq!(t1, 1.0);
         assert_approx_eq!(is.t2().unwrap(), 0.5);
     }
+
+    #[test]
+    fn test_closest_points_segments() {
+        // skew segments crossing above one another
+        let c = closest_points_segments(
+            &Point3::new(-1.0f64, 0.0, 0.0),
+            &Point3::new(1.0, 0.0, 0.0),
+            &Point3::new(0.5, -1.0, 1.0),
+            &Point3::new(0.5, 1.0, 1.0),
+        );
+        assert_approx_eq!(c.s, 0.75);
+        assert_approx_eq!(c.t, 0.5);
+        assert_approx_eq!(c.distance(), 1.0);
+
+        // parallel, non-overlapping segments clamp to the endpoints
+        let c = closest_points_segments(
+            &Point3::new(0.0f64, 0.0, 0.0),
+            &Point3::new(1.0, 0.0, 0.0),
+            &Point3::new(2.0, 1.0, 0.0),
+            &Point3::new(3.0, 1.0, 0.0),
+        );
+        assert_approx_eq!(c.distance(), 2.0f64.sqrt());
+
+        let d: f64 = point_segment_distance(
+            &Point3::new(0.5, 2.0, 0.0),
+            &Point3::new(0.0, 0.0, 0.0),
+            &Point3::new(1.0, 0.0, 0.0),
+        );
+        assert_approx_eq!(d, 2.0);
+    }
+
+    #[test]
+    fn test_ray_primitives() {
+        let origin = Point3::new(0.25, 0.25, 2.0);
+        let dir = Vector3::new(0.0, 0.0, -1.0);
+
+        let t: f64 =
+            ray_plane_intersect(&origin, &dir, &Vector4::new(0.0, 0.0, 1.0, -1.0)).unwrap();
+        assert_approx_eq!(t, 1.0);
+
+        let hit = ray_triangle_intersect(
+            &origin,
+            &dir,
+            &Point3::new(0.0, 0.0, 0.0),
+            &Point3::new(1.0, 0.0, 0.0),
+            &Point3::new(0.0, 1.0, 0.0),
+        )
+        .unwrap();
+        assert_approx_eq!(hit.t, 2.0);
+        assert_approx_eq!(hit.u, 0.25);
+        assert_approx_eq!(hit.v, 0.25);
+        assert!(ray_triangle_intersect(
+            &origin,
+            &-dir,
+            &Point3::new(0.0, 0.0, 0.0),
+            &Point3::new(1.0, 0.0, 0.0),
+            &Point3::new(0.0, 1.0, 0.0),
+        )
+        .is_none());
+
+        let b = Aabb::from_points(&Point3::new(0.0, 0.0, 0.0), &Point3::new(1.0, 1.0, 1.0));
+        let (t0, t1) = ray_aabb_intersect(&origin, &dir, &b).unwrap();
+        assert_approx_eq!(t0, 1.0);
+        assert_approx_eq!(t1, 2.0);
+        assert!(ray_aabb_intersect(&Point3::new(2.0, 0.5, 2.0), &dir, &b).is_none());
+    }
+
+    #[test]
+    fn test_closest_point_on_triangle() {
+        let a = Point3::new(0.0, 0.0, 0.0);
+        let b = Point3::new(2.0, 0.0, 0.0);
+        let c = Point3::new(0.0, 2.0, 0.0);
+        let check = |p: Point3<f64>, expected: Point3<f64>| {
+            let q = closest_point_on_triangle(&p, &a, &b, &c);
+            assert_approx_eq!((q - expected).norm(), 0.0);
+        };
+
+        // inside, above the face
+        check(Point3::new(0.5, 0.5, 3.0), Point3::new(0.5, 0.5, 0.0));
+        // vertex regions
+        check(Point3::new(-1.0, -1.0, 1.0), a);
+        check(Point3::new(3.0, -0.5, 0.0), b);
+        check(Point3::new(-0.5, 3.0, 0.0), c);
+        // edge regions
+        check(Point3::new(1.0, -1.0, 0.0), Point3::new(1.0, 0.0, 0.0));
+        check(Point3::new(-1.0, 1.0, 0.0), Point3::new(0.0, 1.0, 0.0));
+        check(Point3::new(2.0, 2.0, 1.0), Point3::new(1.0, 1.0, 0.0));
+
+        // a degenerate triangle acts as its longest edge
+        let q = closest_point_on_triangle(
+            &Point3::new(1.0, 1.0, 0.0),
+            &a,
+            &b,
+            &Point3::new(1.0, 0.0, 0.0),
+        );
+        assert_approx_eq!((q - Point3::new(1.0, 0.0, 0.0)).norm(), 0.0);
+    }
 }
@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod test {
+    use art_util::Mesh;
+    use assert_approx_eq::assert_approx_eq;
+    use na::{UnitQuaternion, Vector3};
+    use nalgebra as na;
+    use std::f64::consts::PI;
+
+    /// Signed volume of a closed mesh, positive for outward-facing
+    /// triangles.
+    fn volume(m: &Mesh<f64>) -> f64 {
+        m.triangles()
+            .map(|t| {
+                let p = [m.positions[t[0]], m.positions[t[1]], m.positions[t[2]]];
+                p[0].dot(&p[1].cross(&p[2])) / 6.0
+            })
+            .sum()
+    }
+
+    fn check_attributes(m: &Mesh<f64>) {
+        assert!(m.indices.iter().all(|&i| (i as usize) < m.num_vertices()));
+        if let Some(n) = &m.normals {
+            assert_eq!(n.len(), m.num_vertices());
+            for n in n {
+                assert_approx_eq!(n.norm(), 1.0);
+            }
+        }
+        if let Some(uv) = &m.uvs {
+            assert_eq!(uv.len(), m.num_vertices());
+        }
+    }
+
+    #[test]
+    fn test_volumes() {
+        let cases: Vec<(Mesh<f64>, f64, f64)> = vec![
+            (
+                Mesh::cuboid(&Vector3::zeros(), &Vector3::new(1.0, 2.0, 3.0)),
+                48.0,
+                1e-9,
+            ),
+            (Mesh::uv_sphere(2.0, 64, 32), 4.0 / 3.0 * PI * 8.0, 0.2),
+            (Mesh::icosphere(2.0, 4), 4.0 / 3.0 * PI * 8.0, 0.2),
+            (Mesh::cylinder(1.0, 3.0, 128), PI * 3.0, 0.01),
+            (Mesh::cone(1.0, 3.0, 128), PI, 0.01),
+            (
+                Mesh::torus(2.0, 0.5, 128, 64),
+                2.0 * PI * PI * 2.0 * 0.25,
+                0.05,
+            ),
+            (
+                Mesh::capsule(1.0, 2.0, 64, 16),
+                PI * 2.0 + 4.0 / 3.0 * PI,
+                0.05,
+            ),
+            (Mesh::capsule(1.0, 0.0, 64, 16), 4.0 / 3.0 * PI, 0.05),
+        ];
+        for (m, expected, tol) in cases.iter() {
+            check_attributes(m);
+            assert_approx_eq!(volume(m), *expected, *tol);
+        }
+    }
+
+    #[test]
+    fn test_normals_outward() {
+        for m in &[
+            Mesh::uv_sphere(1.0, 16, 8),
+            Mesh::icosphere(1.0, 2),
+            Mesh::capsule(1.0, 1.0, 16, 4),
+        ] {
+            let normals = m.normals.as_ref().unwrap();
+            for (p, n) in m.positions.iter().zip(normals) {
+                assert!(n.dot(&Vector3::new(p.x, 0.0, p.z)) >= -1e-9);
+            }
+            // the same as the computed normals, away from the seams
+            let mut c = m.clone();
+            c.compute_normals();
+            let agree = c
+                .normals
+                .unwrap()
+                .iter()
+                .zip(normals)
+                .filter(|(a, b)| a.dot(b) > 0.9)
+                .count();
+            assert!(agree * 2 > m.num_vertices());
+        }
+    }
+
+    #[test]
+    fn test_counts() {
+        let s = Mesh::<f64>::uv_sphere(1.0, 8, 4);
+        assert_eq!(s.num_vertices(), 9 * 5);
+        // the pole rows have one triangle per segment
+        assert_eq!(s.num_triangles(), 8 * 2 + 8 * 2 * 2);
+
+        let ico = Mesh::<f64>::icosphere(1.0, 2);
+        assert_eq!(ico.num_triangles(), 20 * 16);
+        assert_eq!(ico.num_vertices(), 162);
+
+        let grid = Mesh::<f64>::plane_grid(2.0, 4.0, 4, 2);
+        assert_eq!(grid.num_vertices(), 15);
+        assert_eq!(grid.num_triangles(), 16);
+        assert_approx_eq!(grid.positions[14].x, 1.0);
+        assert_approx_eq!(grid.positions[14].z, 2.0);
+        for t in grid.triangles() {
+            let p = [
+                grid.positions[t[0]],
+                grid.positions[t[1]],
+                grid.positions[t[2]],
+            ];
+            assert!((p[1] - p[0]).cross(&(p[2] - p[0])).y > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_transform() {
+        let mut m = Mesh::uv_sphere(1.0, 32, 16);
+        let v0 = volume(&m);
+        m.scale(&Vector3::new(-2.0, 1.0, 1.0));
+        assert_approx_eq!(volume(&m), 2.0 * v0);
+        m.rotate(&UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.3));
+        m.translate(&Vector3::new(0.0, 0.0, 5.0));
+        assert_approx_eq!(volume(&m), 2.0 * v0);
+        check_attributes(&m);
+
+        let b = m.bounds().unwrap();
+        assert_approx_eq!(b.center().z, 5.0, 1e-2);
+        assert_approx_eq!(b.center().y, 0.0, 1e-9);
+
+        let mut both = Mesh::cuboid(&Vector3::zeros(), &Vector3::new(1.0, 1.0, 1.0));
+        both.append(&Mesh::icosphere(1.0, 0));
+        assert_eq!(both.num_vertices(), 20);
+        assert!(both.normals.is_none());
+        assert_approx_eq!(volume(&both), 8.0 + volume(&Mesh::icosphere(1.0, 0)));
+
+        m.flip();
+        assert_approx_eq!(volume(&m), -2.0 * v0);
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod test {
+    use art_util::mesh::EdgeKind;
+    use art_util::Mesh;
+    use assert_approx_eq::assert_approx_eq;
+    use na::{Point3, Vector3};
+    use nalgebra as na;
+    use std::collections::HashMap;
+
+    const CREASE: f64 = std::f64::consts::FRAC_PI_6;
+
+    #[test]
+    fn test_box_edges() {
+        let cube = Mesh::cuboid(&Vector3::zeros(), &Vector3::new(1.0, 1.0, 1.0));
+        let adj = cube.edge_adjacency();
+        // 12 box edges and a diagonal per face
+        assert_eq!(adj.edges.len(), 18);
+        assert_eq!(adj.all_edges().len(), 36);
+        assert!(adj.boundary_edges().is_empty());
+
+        let creases = adj.crease_edges(CREASE);
+        assert_eq!(creases.len(), 24);
+        for l in creases.chunks(2) {
+            // box edges differ in exactly one coordinate
+            let d = cube.positions[l[0] as usize] - cube.positions[l[1] as usize];
+            assert_eq!(d.iter().filter(|x| **x != 0.0).count(), 1);
+        }
+
+        // looking at a corner, the silhouette is a hexagon of box edges
+        let eye = Point3::new(5.0, 6.0, 7.0);
+        assert_eq!(adj.silhouette_edges(&eye).len(), 12);
+        // silhouettes are all creases already
+        assert_eq!(adj.feature_edges(Some(&eye), CREASE).len(), 24);
+
+        for n in adj.face_normals.iter() {
+            assert_approx_eq!(n.norm(), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_grid_boundary() {
+        let grid = Mesh::<f64>::plane_grid(1.0, 1.0, 2, 2);
+        let adj = grid.edge_adjacency();
+        let boundary = adj.boundary_edges();
+        assert_eq!(boundary.len(), 16);
+        for &i in &boundary {
+            let p = grid.positions[i as usize];
+            assert!(p.x.abs() == 0.5 || p.z.abs() == 0.5);
+        }
+        assert!(adj.crease_edges(CREASE).is_empty());
+
+        let kinds: Vec<_> = (0..adj.edges.len())
+            .map(|i| adj.classify(i, None, CREASE))
+            .collect();
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|k| **k == Some(EdgeKind::Boundary))
+                .count(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_sphere_silhouette() {
+        let sphere = Mesh::uv_sphere(1.0, 32, 16);
+        let adj = sphere.edge_adjacency();
+        // the seam and poles are welded
+        assert!(adj.boundary_edges().is_empty());
+        assert!(adj.crease_edges(CREASE).is_empty());
+
+        let eye = Point3::new(0.0, 0.0, 10.0);
+        let silhouette = adj.silhouette_edges(&eye);
+        assert!(!silhouette.is_empty());
+
+        // a closed loop near the circle facing the eye
+        let mut degree = HashMap::new();
+        for &i in &silhouette {
+            *degree.entry(i).or_insert(0) += 1;
+            assert!(sphere.positions[i as usize].z.abs() < 0.4);
+        }
+        assert!(degree.values().all(|d| *d == 2));
+    }
+
+    #[test]
+    fn test_torus_closed() {
+        // both the axis and tube seams are welded
+        let torus = Mesh::torus(2.0, 0.5, 24, 12);
+        assert!(torus.edge_adjacency().boundary_edges().is_empty());
+    }
+
+    #[test]
+    fn test_cylinder_creases() {
+        let cylinder = Mesh::cylinder(1.0, 2.0, 16);
+        let creases = cylinder.edge_adjacency().crease_edges(CREASE);
+        // the two rims
+        assert_eq!(creases.len(), 2 * 2 * 16);
+        for &i in &creases {
+            assert_approx_eq!(cylinder.positions[i as usize].y.abs(), 1.0);
+        }
+
+        let normals = cylinder.vertex_normals();
+        assert_eq!(normals.len(), cylinder.num_vertices());
+        assert_eq!(cylinder.face_normals().len(), cylinder.num_triangles());
+    }
+}
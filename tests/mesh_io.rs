@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod test {
+    use art_util::mesh::io::{
+        read_mesh, read_obj, read_ply, read_stl, write_mesh, write_obj, write_ply, write_stl,
+        MeshIoError, PlyFormat, StlFormat,
+    };
+    use art_util::Mesh;
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::Vector3;
+    use std::io::Cursor;
+
+    fn assert_same(a: &Mesh<f64>, b: &Mesh<f64>) {
+        assert_eq!(a.num_vertices(), b.num_vertices());
+        assert_eq!(a.indices, b.indices);
+        for (p, q) in a.positions.iter().zip(&b.positions) {
+            assert_approx_eq!((p - q).norm(), 0.0, 1e-6);
+        }
+        assert_eq!(a.normals.is_some(), b.normals.is_some());
+        if let (Some(n), Some(m)) = (&a.normals, &b.normals) {
+            for (p, q) in n.iter().zip(m) {
+                assert_approx_eq!((p - q).norm(), 0.0, 1e-6);
+            }
+        }
+        assert_eq!(a.uvs.is_some(), b.uvs.is_some());
+    }
+
+    #[test]
+    fn test_obj() {
+        let text = "# a square and a triangle
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0 0 1
+vn 0 0 1
+f 1 2 3 4
+f -5 -4 -1
+";
+        let m: Mesh<f64> = read_obj(&mut Cursor::new(text)).unwrap();
+        assert_eq!(m.num_vertices(), 5);
+        assert_eq!(m.indices, vec![0, 1, 2, 0, 2, 3, 0, 1, 4]);
+        assert!(m.normals.is_none());
+
+        // attributes split the shared vertices
+        let text = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvn 0 0 1\nvn 0 0 -1\n\
+                    f 1/1/1 2/2/1 3/1/1\nf 1/1/2 3/1/2 2/2/2\n";
+        let m: Mesh<f64> = read_obj(&mut Cursor::new(text)).unwrap();
+        assert_eq!(m.num_vertices(), 6);
+        assert_eq!(m.normals.as_ref().unwrap()[3].z, -1.0);
+
+        let sphere = Mesh::uv_sphere(1.0, 8, 4);
+        let mut buf = vec![];
+        write_obj(&sphere, &mut buf).unwrap();
+        let read: Mesh<f64> = read_obj(&mut Cursor::new(buf)).unwrap();
+        assert_same(&sphere, &read);
+
+        match read_obj::<f64, _>(&mut Cursor::new("v 0 0 0\nf 1 2 3\n")) {
+            Err(MeshIoError::ParseError(_, Some(2))) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_ply() {
+        let text = "ply
+format ascii 1.0
+comment with extra properties and elements
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+element face 1
+property list uchar int vertex_indices
+property int flags
+element edge 1
+property int vertex1
+property int vertex2
+end_header
+0 0 0 255
+1 0 0 255
+1 1 0 255
+0 1 0 255
+4 0 1 2 3 7
+0 2
+";
+        let m: Mesh<f64> = read_ply(&mut Cursor::new(text)).unwrap();
+        assert_eq!(m.num_vertices(), 4);
+        assert_eq!(m.indices, vec![0, 1, 2, 0, 2, 3]);
+
+        let mut torus = Mesh::torus(2.0, 0.5, 12, 6);
+        torus.translate(&Vector3::new(0.25, -1.5, 3.0));
+        for format in &[
+            PlyFormat::Ascii,
+            PlyFormat::BinaryLittleEndian,
+            PlyFormat::BinaryBigEndian,
+        ] {
+            let mut buf = vec![];
+            write_ply(&torus, &mut buf, *format).unwrap();
+            let read: Mesh<f64> = read_ply(&mut Cursor::new(buf)).unwrap();
+            assert_same(&torus, &read);
+        }
+
+        // negative or fractional counts and indices are rejected
+        let header = "ply
+format ascii 1.0
+element vertex 3
+property float x
+property float y
+property float z
+element face 1
+property list char int vertex_indices
+end_header
+0 0 0
+1 0 0
+0 1 0
+";
+        for face in &["3 0 1 -1\n", "3 0 1 1.5\n", "-3 0 1 2\n"] {
+            let text = format!("{}{}", header, face);
+            match read_ply::<f64, _>(&mut Cursor::new(text)) {
+                Err(MeshIoError::ParseError(_, _)) => {}
+                r => panic!("unexpected {:?} for {}", r, face),
+            }
+        }
+        let text = format!("{}3 0 1 2\n", header);
+        assert!(read_ply::<f64, _>(&mut Cursor::new(text)).is_ok());
+    }
+
+    #[test]
+    fn test_stl() {
+        let cube = Mesh::cuboid(&Vector3::new(0.5, 0.0, 0.0), &Vector3::new(1.0, 2.0, 0.5));
+        for format in &[StlFormat::Ascii, StlFormat::Binary] {
+            let mut buf = vec![];
+            write_stl(&cube, &mut buf, *format).unwrap();
+            if *format == StlFormat::Binary {
+                assert_eq!(buf.len(), 84 + 50 * 12);
+            }
+            // vertices are merged back together
+            let read: Mesh<f64> = read_stl(&mut Cursor::new(buf)).unwrap();
+            assert_eq!(read.num_vertices(), 8);
+            assert_eq!(read.num_triangles(), 12);
+            for t in 0..12 {
+                let a = cube.triangle_positions(t);
+                let b = read.triangle_positions(t);
+                for k in 0..3 {
+                    assert_approx_eq!((a[k] - b[k]).norm(), 0.0, 1e-6);
+                }
+            }
+        }
+
+        // a binary file whose header starts with "solid"
+        let mut buf = vec![];
+        write_stl(&cube, &mut buf, StlFormat::Binary).unwrap();
+        buf[..5].copy_from_slice(b"solid");
+        let read: Mesh<f64> = read_stl(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(read.num_triangles(), 12);
+    }
+
+    #[test]
+    fn test_files() {
+        let cone = Mesh::cone(1.0, 2.0, 16);
+        let dir = std::env::temp_dir();
+        for ext in &["obj", "ply", "stl"] {
+            let path = dir.join(format!("art_util_mesh_io_test.{}", ext));
+            write_mesh(&path, &cone).unwrap();
+            let read: Mesh<f64> = read_mesh(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(read.num_triangles(), cone.num_triangles());
+        }
+        match write_mesh(dir.join("mesh.xyz"), &cone) {
+            Err(MeshIoError::Unsupported(_)) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod test {
+    use art_util::mesh::slice_triangles;
+    use art_util::{add_box, Mesh};
+    use assert_approx_eq::assert_approx_eq;
+    use na::{Point3, Vector3, Vector4};
+    use nalgebra as na;
+
+    /// Twice the signed area of a closed contour, seen from the
+    /// direction `n`.
+    fn signed_area(contour: &[Point3<f64>], n: &Vector3<f64>) -> f64 {
+        contour
+            .windows(2)
+            .map(|w| w[0].coords.cross(&w[1].coords).dot(n))
+            .sum()
+    }
+
+    #[test]
+    fn test_slice_box() {
+        let mut positions: Vec<Vector3<f64>> = vec![];
+        let mut triangles: Vec<u32> = vec![];
+        add_box(
+            &mut positions,
+            &mut triangles,
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 2.0, 3.0),
+        );
+        let contours = slice_triangles(&positions, &triangles, &Vector4::new(0.0, 0.0, 1.0, -0.5));
+        assert_eq!(contours.len(), 1);
+
+        let c = &contours[0];
+        assert_eq!(c.first(), c.last());
+        for p in c {
+            assert_approx_eq!(p.z, 0.5);
+            assert!(p.x.abs() <= 1.0 && p.y.abs() <= 2.0);
+            assert!(p.x.abs() == 1.0 || p.y.abs() == 2.0);
+        }
+        // counter-clockwise from above, around the 2 x 4 section
+        assert_approx_eq!(signed_area(c, &Vector3::z()), 16.0);
+
+        // and clockwise seen from below
+        let flipped = slice_triangles(&positions, &triangles, &Vector4::new(0.0, 0.0, -1.0, 0.5));
+        assert_approx_eq!(signed_area(&flipped[0], &-Vector3::z()), 16.0);
+    }
+
+    #[test]
+    fn test_slice_torus() {
+        let m: Mesh<f64> = Mesh::torus(2.0, 0.5, 32, 16);
+        let contours = m.slice(&Vector4::new(0.0, 1.0, 0.0, 0.0));
+        assert_eq!(contours.len(), 2);
+
+        // the outer contour goes counter-clockwise and the hole
+        // clockwise
+        let mut areas: Vec<f64> = contours
+            .iter()
+            .map(|c| {
+                assert_eq!(c.first(), c.last());
+                signed_area(c, &Vector3::y())
+            })
+            .collect();
+        areas.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(areas[0] < 0.0 && areas[1] > 0.0);
+        assert!(areas[1] > -areas[0]);
+    }
+
+    #[test]
+    fn test_slice_open() {
+        // an open grid gives an open contour
+        let m: Mesh<f64> = Mesh::plane_grid(2.0, 2.0, 4, 4);
+        let contours = m.slice(&Vector4::new(1.0, 0.0, 1.0, -0.1));
+        assert_eq!(contours.len(), 1);
+        assert_ne!(contours[0].first(), contours[0].last());
+
+        // a plane missing the mesh gives nothing
+        assert!(m.slice(&Vector4::new(0.0, 1.0, 0.0, -1.0)).is_empty());
+    }
+
+    #[test]
+    fn test_slice_layers() {
+        let m: Mesh<f64> = Mesh::uv_sphere(1.0, 24, 12);
+        let (offsets, slices) = m.slice_layers(&Vector3::z(), 3);
+        assert_eq!(offsets.len(), 3);
+        assert_approx_eq!(offsets[1], 0.0);
+        for (o, slice) in offsets.iter().zip(&slices) {
+            assert_eq!(slice.len(), 1);
+            for p in &slice[0] {
+                assert_approx_eq!(p.z, *o);
+            }
+        }
+
+        let slices = m.slice_parallel(&Vector3::x(), &[-2.0, 0.25]);
+        assert!(slices[0].is_empty());
+        assert_eq!(slices[1].len(), 1);
+    }
+}
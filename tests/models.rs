@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod test {
+    use art_util::{
+        add_box, add_grid_lines, add_linear_index, add_strips, add_triangle_edges, GridLines,
+    };
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_linear_index() {
+        let open: Vec<u32> = add_linear_index(2, 3, false).collect();
+        assert_eq!(open, vec![2, 3, 3, 4]);
+        let closed: Vec<u32> = add_linear_index(2, 3, true).collect();
+        assert_eq!(closed, vec![2, 3, 3, 4, 4, 2]);
+    }
+
+    #[test]
+    fn test_triangle_edges() {
+        let mut vertices = vec![];
+        let mut triangles: Vec<u16> = vec![];
+        add_box(
+            &mut vertices,
+            &mut triangles,
+            Vector3::<f64>::zeros(),
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        let mut lines = vec![];
+        add_triangle_edges(&mut lines, &triangles);
+        assert_eq!(lines.len(), 2 * 18);
+        // adding them again changes nothing
+        add_triangle_edges(&mut lines, &triangles);
+        assert_eq!(lines.len(), 2 * 18);
+
+        // a quad as two triangles: the shared diagonal appears once
+        let mut lines: Vec<usize> = vec![];
+        add_triangle_edges(&mut lines, &[0, 1, 2, 2, 1, 3]);
+        assert_eq!(lines, vec![0, 1, 1, 2, 2, 0, 1, 3, 3, 2]);
+    }
+
+    #[test]
+    fn test_grid_lines() {
+        let mut rows: Vec<u32> = vec![];
+        add_grid_lines(&mut rows, 10, 3, 2, GridLines::Rows);
+        assert_eq!(rows, vec![10, 11, 11, 12, 13, 14, 14, 15]);
+
+        let mut cols: Vec<u32> = vec![];
+        add_grid_lines(&mut cols, 10, 3, 2, GridLines::Columns);
+        assert_eq!(cols, vec![10, 13, 11, 14, 12, 15]);
+
+        let mut both: Vec<i64> = vec![];
+        add_grid_lines(&mut both, 0, 3, 2, GridLines::Both);
+        assert_eq!(both.len(), rows.len() + cols.len());
+    }
+
+    #[test]
+    fn test_strips() {
+        let mut lines: Vec<u32> = vec![];
+        add_strips(&mut lines, 1, &[3, 1, 2], false);
+        assert_eq!(lines, vec![1, 2, 2, 3, 5, 6]);
+
+        let mut loops: Vec<u32> = vec![];
+        add_strips(&mut loops, 0, &[3, 4], true);
+        assert_eq!(loops, vec![0, 1, 1, 2, 2, 0, 3, 4, 4, 5, 5, 6, 6, 3]);
+        let single: Vec<u32> = add_linear_index(3, 4, true).collect();
+        assert_eq!(&loops[6..], single.as_slice());
+    }
+}
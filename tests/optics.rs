@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod test {
+    use art_util::optics::{Dispersion, Element, Material, Ray2, Shape, TraceOptions};
+    use art_util::{refract_dir, refract_dir_3d, OpticalScene, Refraction};
+    use assert_approx_eq::assert_approx_eq;
+    use na::{Point2, Vector2, Vector3};
+    use nalgebra as na;
+
+    fn glass_slab() -> OpticalScene<f64> {
+        let mut scene = OpticalScene::new();
+        scene.add(Element::new(
+            Shape::Polygon(vec![
+                Point2::new(-10.0, 0.0),
+                Point2::new(10.0, 0.0),
+                Point2::new(10.0, 1.0),
+                Point2::new(-10.0, 1.0),
+            ]),
+            Material::Dielectric(Dispersion::Constant(1.5)),
+        ));
+        scene
+    }
+
+    #[test]
+    fn test_refract_snell() {
+        let theta: f64 = 0.5;
+        let incident = Vector2::new(theta.sin(), -theta.cos());
+        match refract_dir(incident, Vector2::new(0.0, 1.0), 1.0, 1.5) {
+            Refraction::Refracted { dir, reflectance } => {
+                assert_approx_eq!(dir.norm(), 1.0);
+                assert_approx_eq!(dir.x, theta.sin() / 1.5);
+                assert!(dir.y < 0.0);
+                assert!(reflectance > 0.0 && reflectance < 0.1);
+            }
+            r => panic!("expected refraction, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_refract_critical_angle() {
+        let critical = (1.0f64 / 1.5).asin();
+        let normal = Vector3::new(0.0, 0.0, 1.0);
+
+        let past = Vector3::new((critical + 0.1).sin(), 0.0, (critical + 0.1).cos());
+        match refract_dir_3d(past, normal, 1.5, 1.0) {
+            Refraction::TotallyReflected(d) => assert_approx_eq!(d.z, -past.z),
+            r => panic!("expected total reflection, got {:?}", r),
+        }
+
+        let at = Vector3::new(critical.sin(), 0.0, critical.cos());
+        match refract_dir_3d(at, normal, 1.5, 1.0) {
+            Refraction::Grazing { tangent, .. } => assert_approx_eq!(tangent.x, 1.0),
+            r => panic!("expected grazing, got {:?}", r),
+        }
+
+        let before = Vector3::new((critical - 0.1).sin(), 0.0, (critical - 0.1).cos());
+        assert!(matches!(
+            refract_dir_3d(before, normal, 1.5, 1.0),
+            Refraction::Refracted { .. }
+        ));
+    }
+
+    #[test]
+    fn test_slab_parallel_exit() {
+        let scene = glass_slab();
+        let dir = Vector2::new(1.0, -1.0).normalize();
+        let paths = scene.trace(
+            &Ray2::new(Point2::new(0.0, 2.0), dir),
+            &TraceOptions::default(),
+        );
+        assert_eq!(paths.len(), 1);
+        let pts = &paths[0].points;
+        assert_eq!(pts.len(), 4);
+        let exit = (pts[3] - pts[2]).normalize();
+        assert_approx_eq!(exit.x, dir.x, 1e-4);
+        assert_approx_eq!(exit.y, dir.y, 1e-4);
+    }
+
+    #[test]
+    fn test_mirror_and_absorber() {
+        let mut scene: OpticalScene<f32> = OpticalScene::new();
+        scene.add(Element::new(
+            Shape::Segment(Point2::new(-1.0, 0.0), Point2::new(1.0, 0.0)),
+            Material::Mirror,
+        ));
+        scene.add(Element::new(
+            Shape::Circle {
+                center: Point2::new(2.0, 2.0),
+                radius: 0.5,
+            },
+            Material::Absorber,
+        ));
+        let paths = scene.trace(
+            &Ray2::new(Point2::new(-2.0, 2.0), Vector2::new(1.0, -1.0)),
+            &TraceOptions::default(),
+        );
+        let pts = &paths[0].points;
+        assert_eq!(pts.len(), 3);
+        assert_approx_eq!(pts[1].x, 0.0);
+        assert!((pts[2] - Point2::new(2.0, 2.0)).norm() < 0.5 + 1e-4);
+    }
+
+    #[test]
+    fn test_fresnel_split() {
+        let scene = glass_slab();
+        let opts = TraceOptions {
+            fresnel: true,
+            ..TraceOptions::default()
+        };
+        let paths = scene.trace(
+            &Ray2::new(Point2::new(0.0, 2.0), Vector2::new(0.3, -1.0)),
+            &opts,
+        );
+        assert!(paths.len() > 1);
+        let total: f64 = paths
+            .iter()
+            .filter(|p| p.points.last().unwrap().coords.norm() > 100.0)
+            .map(|p| p.intensity)
+            .sum();
+        assert!(total > 0.95 && total <= 1.0 + 1e-8);
+    }
+}
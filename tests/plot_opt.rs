@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod test {
+    use art_util::plot_opt::{optimize_paths, pen_up_distance, PlotOptimizeOptions};
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::Point2;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_merge_chain() {
+        // a polyline broken into shuffled pieces, some reversed
+        let points: Vec<Point2<f64>> = (0..=20).map(|i| Point2::new(i as f64, 1.0)).collect();
+        let mut pieces: Vec<Vec<Point2<f64>>> = points.windows(2).map(|w| w.to_vec()).collect();
+        let mut rng = StdRng::seed_from_u64(3);
+        pieces.shuffle(&mut rng);
+        for p in pieces.iter_mut().step_by(3) {
+            p.reverse();
+        }
+
+        let opts = PlotOptimizeOptions::default();
+        let (paths, stats) = optimize_paths(&pieces, &opts);
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].len(), 21);
+        assert_eq!(paths[0][0], Point2::new(0.0, 1.0));
+        assert_eq!(stats.paths_before, 20);
+        assert_eq!(stats.paths_after, 1);
+        assert_approx_eq!(stats.travel_after, 1.0);
+        assert!(stats.travel_before > 20.0);
+    }
+
+    #[test]
+    fn test_reorder_segments() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let segments: Vec<Vec<Point2<f64>>> = (0..2000)
+            .map(|_| {
+                let a = Point2::new(rng.gen_range(0.0, 100.0), rng.gen_range(0.0, 100.0));
+                let d = nalgebra::Vector2::new(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0));
+                vec![a, a + d]
+            })
+            .collect();
+
+        let opts = PlotOptimizeOptions {
+            merge_tolerance: None,
+            ..Default::default()
+        };
+        let (paths, stats) = optimize_paths(&segments, &opts);
+        assert_eq!(paths.len(), segments.len());
+        assert_approx_eq!(stats.travel_after, pen_up_distance(&paths, &opts.origin));
+        assert!(stats.travel_after < stats.travel_before * 0.1);
+
+        // every segment is drawn exactly once, in either direction
+        let key = |p: &Vec<Point2<f64>>| {
+            let (a, b) = (p[0], p[1]);
+            if (a.x, a.y) < (b.x, b.y) {
+                [a.x, a.y, b.x, b.y]
+            } else {
+                [b.x, b.y, a.x, a.y]
+            }
+        };
+        let mut before: Vec<_> = segments.iter().map(key).collect();
+        let mut after: Vec<_> = paths.iter().map(key).collect();
+        before.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        after.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(before, after);
+
+        // 2-opt only improves on the greedy ordering
+        let greedy = PlotOptimizeOptions {
+            two_opt_passes: 0,
+            ..opts
+        };
+        let (_, greedy_stats) = optimize_paths(&segments, &greedy);
+        assert!(stats.travel_after < greedy_stats.travel_after);
+    }
+}
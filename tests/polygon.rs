@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod test {
+    use art_util::{
+        centroid, is_convex, is_simple, orient_2d, orientation, perimeter,
+        point_in_polygon_even_odd, point_in_polygon_winding, self_intersections, signed_area,
+        winding_number, Orientation, PointTest,
+    };
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::Point2;
+
+    fn square() -> Vec<Point2<f64>> {
+        vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(2.0, 0.0),
+            Point2::new(2.0, 2.0),
+            Point2::new(0.0, 2.0),
+        ]
+    }
+
+    #[test]
+    fn test_measures() {
+        let mut sq = square();
+        assert_approx_eq!(signed_area(&sq), 4.0);
+        assert_approx_eq!(perimeter(&sq), 8.0);
+        let c = centroid(&sq).unwrap();
+        assert_approx_eq!(c.x, 1.0);
+        assert_approx_eq!(c.y, 1.0);
+        assert_eq!(orientation(&sq), Orientation::CounterClockwise);
+        assert!(is_convex(&sq));
+
+        sq.reverse();
+        assert_approx_eq!(signed_area(&sq), -4.0);
+        assert_eq!(orientation(&sq), Orientation::Clockwise);
+        assert!(is_convex(&sq));
+    }
+
+    #[test]
+    fn test_point_queries() {
+        let sq = square();
+        let inside = Point2::new(1.0, 1.0);
+        let edge = Point2::new(2.0, 1.0);
+        let outside = Point2::new(3.0, 1.0);
+        let beside_edge = Point2::new(2.5, 1.0);
+
+        assert_eq!(winding_number(&inside, &sq), 1);
+        assert_eq!(point_in_polygon_winding(&inside, &sq), PointTest::Inside);
+        assert_eq!(point_in_polygon_winding(&edge, &sq), PointTest::On);
+        assert_eq!(point_in_polygon_even_odd(&outside, &sq), PointTest::Outside);
+        assert_eq!(
+            point_in_polygon_winding(&beside_edge, &sq),
+            PointTest::Outside
+        );
+
+        // a pentagram covers its center twice: inside by winding, but
+        // outside by even-odd.
+        let star: Vec<_> = (0..5)
+            .map(|i| {
+                let a = std::f64::consts::FRAC_PI_2 + i as f64 * 4.0 * std::f64::consts::PI / 5.0;
+                Point2::new(a.cos(), a.sin())
+            })
+            .collect();
+        let origin = Point2::new(0.0, 0.0);
+        assert_eq!(winding_number(&origin, &star).abs(), 2);
+        assert_eq!(point_in_polygon_winding(&origin, &star), PointTest::Inside);
+        assert_eq!(
+            point_in_polygon_even_odd(&origin, &star),
+            PointTest::Outside
+        );
+        assert!(!is_convex(&star));
+        assert_eq!(self_intersections(&star).len(), 5);
+    }
+
+    #[test]
+    fn test_self_intersection() {
+        assert!(is_simple(&square()));
+
+        let bowtie = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(0.0, 1.0),
+        ];
+        assert_eq!(self_intersections(&bowtie), vec![(0, 2)]);
+        assert!(!is_simple(&bowtie));
+        assert!(!is_convex(&bowtie));
+    }
+
+    #[test]
+    fn test_orient_2d() {
+        let a = Point2::new(0.0, 0.0);
+        let b = Point2::new(2.0, 1.0);
+
+        // left of the line from a to b is inside, right is outside
+        assert_eq!(orient_2d(Point2::new(0.0, 1.0), a, b), PointTest::Inside);
+        assert_eq!(orient_2d(Point2::new(1.0, -1.0), a, b), PointTest::Outside);
+        assert_eq!(orient_2d(Point2::new(1.0, 0.5), a, b), PointTest::On);
+        assert_eq!(orient_2d(Point2::new(4.0, 2.0), a, b), PointTest::On);
+
+        // reversing the line swaps the sides
+        assert_eq!(orient_2d(Point2::new(1.0, -1.0), b, a), PointTest::Inside);
+        assert_eq!(orient_2d(Point2::new(0.0, 1.0), b, a), PointTest::Outside);
+    }
+}
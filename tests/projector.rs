@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod test {
+    use art_util::{Camera, Projector, Rect};
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::{Point2, Point3, Vector3};
+
+    fn projector() -> Projector<f64> {
+        let page = Rect::from_points(&Point2::new(0.0, 0.0), &Point2::new(100.0, 100.0));
+        let camera = Camera::orthographic(
+            Point3::new(0.0, 0.0, 5.0),
+            Point3::origin(),
+            Vector3::y(),
+            2.0,
+            1.0,
+            0.1,
+            10.0,
+        );
+        Projector::from_camera(&camera, &page)
+    }
+
+    #[test]
+    fn test_inside() {
+        let proj = projector();
+        let pl = vec![
+            Point3::new(-0.5, -0.5, 0.0),
+            Point3::new(0.5, -0.5, 0.0),
+            Point3::new(0.5, 0.5, 0.0),
+        ];
+        let out = proj.project_polyline(&pl);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].len(), 3);
+        assert_approx_eq!(out[0][0].x, 25.0);
+        assert_approx_eq!(out[0][0].y, 75.0);
+        assert_approx_eq!(out[0][2].y, 25.0);
+    }
+
+    #[test]
+    fn test_split() {
+        let proj = projector();
+
+        // zig-zag leaving the view on the right and coming back
+        let pl = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.5, 0.0, 0.0),
+            Point3::new(2.0, 0.5, 0.0),
+            Point3::new(0.5, 0.5, 0.0),
+            Point3::new(0.0, 0.5, 0.0),
+            // passes through, with both ends outside
+            Point3::new(-1.5, 0.0, 0.0),
+            Point3::new(0.0, 1.5, 0.0),
+            // entirely outside
+            Point3::new(3.0, 2.0, 0.0),
+        ];
+        let out = proj.project_polyline(&pl);
+        assert_eq!(out.len(), 3);
+        assert_eq!(out[0].len(), 3);
+        assert_approx_eq!(out[0][2].x, 100.0);
+        assert_eq!(out[1].len(), 4);
+        assert_approx_eq!(out[1][0].x, 100.0);
+        assert_eq!(out[2].len(), 2);
+        assert_approx_eq!(out[2][0].x, 0.0);
+        assert_approx_eq!(out[2][1].y, 0.0);
+
+        let all = proj.project_polylines(&[pl.clone(), pl]);
+        assert_eq!(all.len(), 6);
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod test {
+    use art_util::mesh::{catmull_clark, loop_subdivide, quads_to_triangles};
+    use art_util::Mesh;
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::Vector3;
+
+    /// A cube with corners at +-1, as quads facing outward.
+    fn quad_cube() -> (Vec<Vector3<f64>>, Vec<u32>) {
+        let mut positions = vec![];
+        for i in 0..8 {
+            let c = |b: usize| if i & b != 0 { 1.0 } else { -1.0 };
+            positions.push(Vector3::new(c(1), c(2), c(4)));
+        }
+        let quads = vec![
+            0, 2, 3, 1, 4, 5, 7, 6, 0, 1, 5, 4, 2, 6, 7, 3, 0, 4, 6, 2, 1, 3, 7, 5,
+        ];
+        (positions, quads)
+    }
+
+    fn cube_edges() -> Vec<[u32; 2]> {
+        let mut edges = vec![];
+        for a in 0..8u32 {
+            for b in &[1, 2, 4] {
+                if a & b == 0 {
+                    edges.push([a, a | b]);
+                }
+            }
+        }
+        edges
+    }
+
+    fn volume(positions: &[Vector3<f64>], triangles: &[u32]) -> f64 {
+        triangles
+            .chunks(3)
+            .map(|t| {
+                let p = |i: usize| positions[t[i] as usize];
+                p(0).dot(&p(1).cross(&p(2))) / 6.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_catmull_clark() {
+        let (positions, quads) = quad_cube();
+        assert_approx_eq!(volume(&positions, &quads_to_triangles(&quads)), 8.0);
+
+        let (p1, q1) = catmull_clark(&positions, &quads, &[], 1);
+        assert_eq!(p1.len(), 8 + 6 + 12);
+        assert_eq!(q1.len(), 4 * 24);
+        // the corner rule for valence 3
+        for x in p1[7].iter() {
+            assert_approx_eq!(*x, 5.0 / 9.0);
+        }
+
+        let (p3, q3) = catmull_clark(&positions, &quads, &[], 3);
+        let v = volume(&p3, &quads_to_triangles(&q3));
+        assert!(v > 0.0 && v < 8.0);
+
+        // with every edge creased, the cube keeps its shape
+        let (p, q) = catmull_clark(&positions, &quads, &cube_edges(), 3);
+        assert_eq!(p[7], positions[7]);
+        for x in &p {
+            assert_approx_eq!(x.amax(), 1.0f64);
+        }
+        assert_approx_eq!(volume(&p, &quads_to_triangles(&q)), 8.0);
+    }
+
+    #[test]
+    fn test_loop() {
+        let ico = Mesh::<f64>::icosphere(1.0, 0);
+        let (p, t) = loop_subdivide(&ico.positions, &ico.indices, &[], 1);
+        assert_eq!(p.len(), 12 + 30);
+        assert_eq!(t.len(), 3 * 80);
+        for x in &p {
+            assert!(x.norm() < 1.0);
+        }
+        assert!(volume(&p, &t) > 0.0);
+
+        // creases keep the corners and edges of a box
+        let cube = Mesh::cuboid(&Vector3::zeros(), &Vector3::new(1.0, 1.0, 1.0));
+        let (p, t) = loop_subdivide(&cube.positions, &cube.indices, &cube_edges(), 2);
+        assert_eq!(&p[..8], cube.positions.as_slice());
+        for x in &p {
+            assert_approx_eq!(x.amax(), 1.0f64);
+        }
+        assert_approx_eq!(volume(&p, &t), 8.0);
+
+        let smooth = cube.subdivide_loop(&[], 2);
+        assert_eq!(smooth.num_triangles(), 12 * 16);
+        assert!(smooth.normals.is_some());
+        assert!(volume(&smooth.positions, &smooth.indices) < 8.0);
+    }
+
+    #[test]
+    fn test_loop_seams() {
+        // the seam and pole vertices of a UV sphere are merged, so the
+        // surface shrinks smoothly, without creases or spikes
+        let sphere = Mesh::<f64>::uv_sphere(1.0, 16, 8).subdivide_loop(&[], 2);
+        assert!(sphere.edge_adjacency().boundary_edges().is_empty());
+        let radii: Vec<f64> = sphere.positions.iter().map(|p| p.norm()).collect();
+        let lo = radii.iter().cloned().fold(f64::INFINITY, f64::min);
+        let hi = radii.iter().cloned().fold(0.0, f64::max);
+        assert!(lo > 0.95 && hi < 0.98);
+
+        // and points at the same height, including across the seam,
+        // are at the same radius
+        for (p, r) in sphere.positions.iter().zip(&radii) {
+            for (q, s) in sphere.positions.iter().zip(&radii) {
+                if (p.y - q.y).abs() < 1e-9 {
+                    assert_approx_eq!(r, s, 1e-3);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_loop_boundary() {
+        let grid = Mesh::<f64>::plane_grid(1.0, 1.0, 2, 2);
+        let (p, t) = loop_subdivide(&grid.positions, &grid.indices, &[], 2);
+        assert_eq!(t.len(), grid.indices.len() * 16);
+        for x in &p {
+            assert_eq!(x.y, 0.0);
+            assert!(x.x.abs() <= 0.5 && x.z.abs() <= 0.5);
+        }
+
+        // boundary vertices follow the crease rule along the boundary
+        let (p, _) = loop_subdivide(&grid.positions, &grid.indices, &[], 1);
+        assert_approx_eq!(p[0].x, -0.4375);
+        assert_approx_eq!(p[0].z, -0.4375);
+        assert_approx_eq!(p[1].x, 0.0);
+        assert_approx_eq!(p[1].z, -0.5);
+    }
+}
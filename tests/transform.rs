@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod test {
+    use art_util::svg::transform_group;
+    use art_util::{Rect, Transform2};
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::{Point2, Vector2};
+    use svg::Node;
+
+    fn assert_point_eq(a: Point2<f64>, b: Point2<f64>) {
+        assert_approx_eq!(a.x, b.x);
+        assert_approx_eq!(a.y, b.y);
+    }
+
+    #[test]
+    fn test_compose_invert() {
+        let p = Point2::new(1.0, 2.0);
+        let t = Transform2::translation(&Vector2::new(1.0, 0.0))
+            .then(&Transform2::rotation(std::f64::consts::FRAC_PI_2));
+        assert_point_eq(t.apply_point(&p), Point2::new(-2.0, 2.0));
+
+        // `a * b` applies b first
+        let r = Transform2::rotation(std::f64::consts::FRAC_PI_2)
+            * Transform2::translation(&Vector2::new(1.0, 0.0));
+        assert_eq!(r, t);
+
+        let about = Transform2::rotation_about(std::f64::consts::PI, &Point2::new(1.0, 1.0));
+        assert_point_eq(about * p, Point2::new(1.0, 0.0));
+
+        let s = Transform2::shear(0.5, 0.0).then(&Transform2::scaling(2.0, 3.0));
+        assert_point_eq(s.apply_point(&p), Point2::new(4.0, 6.0));
+        assert_point_eq(s.inverse().unwrap().apply_point(&Point2::new(4.0, 6.0)), p);
+        assert!(Transform2::scaling(1.0, 0.0).inverse().is_none());
+    }
+
+    #[test]
+    fn test_rect_fit() {
+        let r = Rect::from_points(&Point2::new(0.0, 0.0), &Point2::new(2.0, 1.0));
+        let b = Transform2::rotation(std::f64::consts::FRAC_PI_4).apply_rect(&r);
+        assert_approx_eq!(b.dim().x, 1.5 * 2f64.sqrt());
+        assert_approx_eq!(b.dim().y, 1.5 * 2f64.sqrt());
+
+        // a 2x1 box into a square page keeps its aspect and is centered
+        let page = Rect::from_points(&Point2::new(10.0, 10.0), &Point2::new(20.0, 20.0));
+        let t = Transform2::fit_rect(&r, &page, false);
+        let fitted = t.apply_rect(&r);
+        assert_point_eq(fitted.p[0], Point2::new(10.0, 12.5));
+        assert_point_eq(fitted.p[1], Point2::new(20.0, 17.5));
+
+        // flipping y maps the top of the drawing to the top of the page
+        let t = Transform2::fit_rect(&r, &page, true);
+        assert_point_eq(
+            t.apply_point(&Point2::new(0.0, 1.0)),
+            Point2::new(10.0, 12.5),
+        );
+
+        let t = Transform2::fit_points(
+            &[Point2::new(0.0, 0.0), Point2::new(2.0, 1.0)],
+            &page,
+            1.0,
+            false,
+        );
+        assert_point_eq(
+            t.apply_point(&Point2::new(0.0, 0.0)),
+            Point2::new(11.0, 13.0),
+        );
+
+        let t = Transform2::stretch_rect(&r, &page, false);
+        assert_point_eq(
+            t.apply_point(&Point2::new(2.0, 1.0)),
+            Point2::new(20.0, 20.0),
+        );
+
+        // a flat src is stretched along x and centered along y
+        let flat = Rect::from_points(&Point2::new(0.0, 3.0), &Point2::new(2.0, 3.0));
+        let t = Transform2::stretch_rect(&flat, &page, false);
+        assert_point_eq(
+            t.apply_point(&Point2::new(2.0, 3.0)),
+            Point2::new(20.0, 15.0),
+        );
+    }
+
+    #[test]
+    fn test_svg_attribute() {
+        let t =
+            Transform2::translation(&Vector2::new(3.0, 4.0)).then(&Transform2::scaling(2.0, 1.0));
+        assert_eq!(t.to_svg_attribute(), "matrix(2 0 0 1 6 4)");
+        let g = transform_group(svg::node::element::Group::new(), &t);
+        assert_eq!(
+            g.get_attributes().unwrap()["transform"].to_string(),
+            "matrix(2 0 0 1 6 4)"
+        );
+    }
+}
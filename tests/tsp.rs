@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod test {
+    use art_util::tsp::{tour_length, tsp_tour, TspOptions};
+    use art_util::PoissonSampling;
+    use assert_approx_eq::assert_approx_eq;
+    use nalgebra::Point2;
+    use rand::prelude::*;
+
+    fn random_points(n: usize, seed: u64) -> Vec<Point2<f64>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| Point2::new(rng.gen_range(0.0, 1.0), rng.gen_range(0.0, 1.0)))
+            .collect()
+    }
+
+    fn is_permutation(tour: &[usize], n: usize) -> bool {
+        let mut sorted = tour.to_vec();
+        sorted.sort_unstable();
+        sorted == (0..n).collect::<Vec<_>>()
+    }
+
+    fn count_crossings(points: &[Point2<f64>], tour: &[usize]) -> usize {
+        let n = tour.len();
+        let orient = |o: Point2<f64>, a: Point2<f64>, b: Point2<f64>| {
+            ((a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)).signum()
+        };
+        let mut count = 0;
+        for i in 0..n {
+            for j in (i + 2)..n {
+                if i == 0 && j == n - 1 {
+                    continue;
+                }
+                let (a, b) = (points[tour[i]], points[tour[(i + 1) % n]]);
+                let (c, d) = (points[tour[j]], points[tour[(j + 1) % n]]);
+                if orient(a, b, c) * orient(a, b, d) < 0.0
+                    && orient(c, d, a) * orient(c, d, b) < 0.0
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_square() {
+        let points = vec![
+            Point2::new(0.0f64, 0.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(0.0, 1.0),
+            Point2::new(0.5, 0.0),
+        ];
+        let tour = tsp_tour(&points, &TspOptions::default());
+        assert_eq!(tour[0], 0);
+        assert_approx_eq!(tour_length(&points, &tour), 4.0);
+    }
+
+    #[test]
+    fn test_random_tour() {
+        let n = 3000;
+        let points = random_points(n, 2);
+        let tour = tsp_tour(&points, &TspOptions::default());
+        assert!(is_permutation(&tour, n));
+
+        // the optimal tour of uniform points in the unit square has
+        // length around 0.7124 sqrt(n), plus a boundary effect
+        let len = tour_length(&points, &tour);
+        assert!(len < 0.7124 * (n as f64).sqrt() * 1.12, "{}", len);
+
+        let two_opt = TspOptions {
+            or_opt: false,
+            ..Default::default()
+        };
+        let tour_2opt = tsp_tour(&points, &two_opt);
+        assert!(is_permutation(&tour_2opt, n));
+        assert!(len < tour_length(&points, &tour_2opt));
+    }
+
+    #[test]
+    fn test_no_crossings() {
+        let sampler = PoissonSampling::new(0.04, 1.0, 1.0);
+        let mut rng = StdRng::seed_from_u64(11);
+        let points: Vec<Point2<f64>> = sampler
+            .into_points(&mut rng)
+            .iter()
+            .map(|p| Point2::new(p.x as f64, p.y as f64))
+            .take(600)
+            .collect();
+
+        let opts = TspOptions {
+            neighbors: 5,
+            no_crossings: true,
+            ..Default::default()
+        };
+        let tour = tsp_tour(&points, &opts);
+        assert!(is_permutation(&tour, points.len()));
+        assert_eq!(count_crossings(&points, &tour), 0);
+    }
+}